@@ -0,0 +1,142 @@
+use crate::config::accounts::{self, AccountStore};
+use crate::config::paths::Paths;
+use crate::error::AppResult;
+use anyhow::Context;
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 保存当前 Claude 凭据为账号
+pub fn save(paths: &Paths, name: &str, force: bool) -> AppResult<i32> {
+    let store = AccountStore::new(&paths.cache_dir);
+    let credentials_path = accounts::credentials_path()?;
+    let _lock = store.lock()?;
+
+    if !credentials_path.is_file() {
+        eprintln!("错误: 未找到 Claude 凭据: {}", credentials_path.display());
+        eprintln!("提示: 先运行 `claude` 并使用 /login 登录订阅账号");
+        return Ok(1);
+    }
+    let credentials = fs::read(&credentials_path)
+        .with_context(|| format!("读取失败: {}", credentials_path.display()))?;
+    accounts::check_credentials(&credentials)?;
+
+    if store.exists(name) && !force {
+        eprintln!("错误: 账号 '{}' 已存在", name);
+        eprintln!("提示: 使用 --force 覆盖");
+        return Ok(1);
+    }
+    let oauth = accounts::read_oauth_account(&accounts::main_state_path()?);
+    store.save(name, &credentials, oauth.as_ref())?;
+    store.set_active(Some(name))?;
+    eprintln!("✓ 已保存账号: {}（{}）", name, accounts::describe(oauth.as_ref()));
+    Ok(0)
+}
+
+/// 将账号凭据切换为主配置目录中的当前凭据
+pub fn use_account(paths: &Paths, name: &str, force: bool) -> AppResult<i32> {
+    let store = AccountStore::new(&paths.cache_dir);
+    let credentials_path = accounts::credentials_path()?;
+    let _lock = store.lock()?;
+
+    if !store.exists(name) {
+        eprintln!("错误: 账号不存在: {}", name);
+        eprintln!("提示: 使用 `ccstart account list` 查看已保存的账号");
+        return Ok(1);
+    }
+    let state_path = accounts::main_state_path()?;
+    if credentials_path.is_file() {
+        let current = fs::read(&credentials_path)
+            .with_context(|| format!("读取失败: {}", credentials_path.display()))?;
+        let current_oauth = accounts::read_oauth_account(&state_path);
+        match store.active() {
+            Some(active) if store.exists(&active) => {
+                if current != store.read(&active)? {
+                    if store.same_account(&active, current_oauth.as_ref()) {
+                        // claude 会刷新 token，切换前把当前凭据写回原账号
+                        store.save(&active, &current, current_oauth.as_ref())?;
+                        eprintln!("[INFO] 已同步账号 '{}' 的最新凭据", active);
+                    } else if !force {
+                        // 在 claude 中 /login 了其它账号：不能覆盖原账号
+                        eprintln!(
+                            "错误: 当前登录的是 {}，与账号 '{}'（{}）不一致",
+                            accounts::describe(current_oauth.as_ref()),
+                            active,
+                            accounts::describe(store.oauth_account(&active).as_ref())
+                        );
+                        eprintln!("提示: 先使用 `ccstart account save <新名称>` 保存当前登录，或使用 --force 放弃当前凭据");
+                        return Ok(1);
+                    } else {
+                        eprintln!("[WARN] 已放弃当前登录（{}）的凭据", accounts::describe(current_oauth.as_ref()));
+                    }
+                }
+            }
+            _ => {
+                if !force && store.find_matching(&current)?.is_none() {
+                    eprintln!("错误: 当前凭据尚未保存为账号，切换后将丢失");
+                    eprintln!("提示: 先使用 `ccstart account save <name>` 保存，或使用 --force 覆盖");
+                    return Ok(1);
+                }
+            }
+        }
+    }
+
+    // 同步之后再读取：切换到当前账号时使用刚写回的最新凭据
+    let target = store.read(name)?;
+    if let Some(dir) = credentials_path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("创建目录失败: {}", dir.display()))?;
+    }
+    accounts::write_private_atomic(&credentials_path, &target)?;
+    if let Some(oauth) = store.oauth_account(name) {
+        accounts::write_oauth_account(&state_path, &oauth)?;
+    }
+    store.set_active(Some(name))?;
+    eprintln!("✓ 已切换到账号: {}", name);
+    Ok(0)
+}
+
+/// 列出已保存的账号（* 表示当前账号）
+pub fn list(paths: &Paths) -> AppResult<i32> {
+    let store = AccountStore::new(&paths.cache_dir);
+    let accounts = store.list()?;
+    if accounts.is_empty() {
+        eprintln!("[INFO] 暂无已保存的账号（目录: {}）", store.dir().display());
+        eprintln!("提示: 登录后使用 `ccstart account save <name>` 保存当前账号");
+        return Ok(0);
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0);
+    let width = accounts.iter().map(|a| a.name.chars().count()).max().unwrap_or(0);
+    for account in &accounts {
+        let mark = if account.active { "*" } else { " " };
+        let expiry = match account.expires_at {
+            Some(at) if at <= now => "token 已过期（启动时自动刷新）".to_string(),
+            Some(at) => format!("token {} 分钟后过期", (at - now) / 60_000),
+            None => String::new(),
+        };
+        println!(
+            "{} {:<width$}  {:<8}  {:<24}  {}",
+            mark,
+            account.name,
+            account.subscription.as_deref().unwrap_or("-"),
+            account.email.as_deref().unwrap_or("-"),
+            expiry
+        );
+    }
+    Ok(0)
+}
+
+/// 删除已保存的账号
+pub fn remove(paths: &Paths, name: &str) -> AppResult<i32> {
+    let store = AccountStore::new(&paths.cache_dir);
+    let _lock = store.lock()?;
+    if !store.exists(name) {
+        eprintln!("错误: 账号不存在: {}", name);
+        return Ok(1);
+    }
+    store.remove(name)?;
+    eprintln!("✓ 已删除账号: {}", name);
+    Ok(0)
+}
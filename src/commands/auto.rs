@@ -0,0 +1,73 @@
+use crate::commands::run::{run_preset, RunOptions};
+use crate::config::file::ConfigFile;
+use crate::config::paths::Paths;
+use crate::config::rules::RuleContext;
+use crate::db::AppType;
+use crate::error::AppResult;
+use crate::source::ProviderSource;
+
+/// 按配置文件中的 `[[rules]]` 顺序求值，使用第一条命中的规则启动；`explain` 时只输出判断过程
+pub fn auto(
+    source: &dyn ProviderSource,
+    paths: &Paths,
+    app: AppType,
+    args: &[String],
+    opts: &RunOptions,
+    explain: bool,
+) -> AppResult<i32> {
+    let rules = ConfigFile::load()?.rules()?;
+    if rules.is_empty() {
+        eprintln!("错误: 配置文件中没有 [[rules]]: {}", ConfigFile::path()?.display());
+        eprintln!("提示: 参见 README 中的“自动选择”一节");
+        return Ok(1);
+    }
+
+    let ctx = RuleContext::current()?;
+    let mut fired = None;
+    for (index, rule) in rules.iter().enumerate() {
+        let label = rule.label(index);
+        let invalid = |e: anyhow::Error| anyhow::anyhow!("规则 {} 无效: {}", label, e);
+        let rule_app = rule.app_type().map_err(invalid)?;
+        if rule_app != app {
+            if explain {
+                println!("- {}  跳过（{} 规则）", label, rule_app.label());
+            }
+            continue;
+        }
+        let checks = rule.evaluate(&ctx).map_err(invalid)?;
+        let matched = checks.iter().all(|c| c.matched);
+        if explain {
+            println!("{} {} -> {}", if matched { "✓" } else { "✗" }, label, rule.provider);
+            if checks.is_empty() {
+                println!("    (无条件)");
+            }
+            for check in &checks {
+                println!(
+                    "    {} {}（当前: {}）",
+                    if check.matched { "✓" } else { "✗" },
+                    check.condition,
+                    check.actual
+                );
+            }
+        }
+        if matched {
+            fired = Some((label, rule));
+            break;
+        }
+    }
+
+    let Some((label, rule)) = fired else {
+        eprintln!("错误: 没有匹配当前环境的 {} 规则", app.label());
+        if !explain {
+            eprintln!("提示: 使用 `ccstart auto --explain` 查看各规则的判断结果");
+        }
+        return Ok(1);
+    };
+    if explain {
+        println!("命中规则 {}: {}", label, rule.provider);
+        return Ok(0);
+    }
+
+    eprintln!("[INFO] 命中规则 {}: {}", label, rule.provider);
+    run_preset(source, paths, &rule.target(), args, opts)
+}
@@ -0,0 +1,173 @@
+use crate::config::paths::Paths;
+use crate::db::{AppType, BackupStore, Database, DbMode, Provider};
+use crate::error::AppResult;
+use crate::source::ProviderSource;
+use clap::ValueEnum;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::io::{self, BufRead, IsTerminal, Write};
+
+/// 差异中最多列出的 settings 字段数
+const MAX_DIFF_PATHS: usize = 5;
+
+fn store(paths: &Paths) -> BackupStore {
+    BackupStore::new(paths.backup_dir.clone(), paths.backup_keep)
+}
+
+/// 创建快照，或列出已有快照
+pub fn backup(source: &dyn ProviderSource, paths: &Paths, list: bool) -> AppResult<i32> {
+    let store = store(paths);
+    if list {
+        let entries = store.list()?;
+        if entries.is_empty() {
+            eprintln!("[INFO] 暂无快照（备份目录: {}）", store.dir().display());
+            return Ok(0);
+        }
+        for (i, entry) in entries.iter().enumerate() {
+            println!(
+                "{:>3}  {} UTC  {:<11}  {:>8}  {}",
+                i + 1,
+                entry.created,
+                entry.reason,
+                format_size(entry.size),
+                entry.name
+            );
+        }
+        eprintln!("[INFO] 备份目录: {}（每种原因保留最近 {} 份）", store.dir().display(), paths.backup_keep);
+        return Ok(0);
+    }
+
+    let db = source.require_database()?;
+    let path = store.create(db, "manual")?;
+    eprintln!("✓ 已备份: {}", path.display());
+    Ok(0)
+}
+
+/// 从快照恢复数据库：先列出 provider 差异，确认后备份当前数据库再覆盖
+pub fn restore(source: &dyn ProviderSource, paths: &Paths, snapshot: &str, yes: bool) -> AppResult<i32> {
+    let db = source.require_database()?;
+    db.check_writable()?;
+    let store = store(paths);
+    let snapshot_path = store.find(snapshot)?;
+    let snapshot_db = Database::open(&snapshot_path, DbMode::Immutable)?;
+
+    eprintln!("[INFO] 快照: {}", snapshot_path.display());
+    let mut changes = 0;
+    for app in AppType::value_variants() {
+        changes += print_diff(*app, &db.providers().list_all(*app)?, &snapshot_db.providers().list_all(*app)?);
+    }
+    if changes == 0 {
+        eprintln!("[INFO] provider 与当前数据库一致（其它表仍会按快照恢复）");
+    } else {
+        eprintln!("[INFO] 共 {} 处 provider 差异（+ 恢复，- 删除，~ 修改）", changes);
+    }
+
+    if !yes && !io::stdin().is_terminal() {
+        eprintln!("错误: 非交互环境下恢复需要 --yes 确认");
+        return Ok(1);
+    }
+    if !yes {
+        eprint!("确认用快照覆盖当前数据库？[y/N] ");
+        io::stderr().flush()?;
+        let mut answer = String::new();
+        io::stdin().lock().read_line(&mut answer)?;
+        if !matches!(answer.trim(), "y" | "Y" | "yes") {
+            eprintln!("[INFO] 已取消");
+            return Ok(1);
+        }
+    }
+
+    // 恢复前始终备份当前数据库，便于撤销；清理旧快照时保留正要恢复的快照
+    let current = store.create_protecting(db, "pre-restore", Some(&snapshot_path))?;
+    eprintln!("[INFO] 已备份当前数据库: {}", current.display());
+    db.restore_from(&snapshot_db)?;
+    eprintln!("✓ 已从快照恢复: {}", snapshot_path.display());
+    eprintln!("提示: 运行 `ccstart update` 刷新缓存的配置文件");
+    Ok(0)
+}
+
+/// 按 id 对比两组 provider，输出差异并返回差异数
+fn print_diff(app: AppType, current: &[Provider], snapshot: &[Provider]) -> usize {
+    let mut changes = 0;
+    for old in snapshot {
+        match current.iter().find(|p| p.id == old.id) {
+            None => {
+                println!("+ {}/{}", app, old.name);
+                changes += 1;
+            }
+            Some(now) => {
+                let fields = changed_fields(now, old);
+                if !fields.is_empty() {
+                    println!("~ {}/{}: {}", app, now.name, fields.join("、"));
+                    changes += 1;
+                }
+            }
+        }
+    }
+    for now in current {
+        if !snapshot.iter().any(|p| p.id == now.id) {
+            println!("- {}/{}", app, now.name);
+            changes += 1;
+        }
+    }
+    changes
+}
+
+/// 描述从当前状态恢复到快照时会改变的字段（不显示值，避免泄露密钥）
+fn changed_fields(now: &Provider, old: &Provider) -> Vec<String> {
+    let mut fields = Vec::new();
+    if now.name != old.name {
+        fields.push(format!("名称 -> {}", old.name));
+    }
+    if now.settings_config != old.settings_config {
+        let mut paths = BTreeSet::new();
+        diff_paths(&now.settings_config, &old.settings_config, "", &mut paths);
+        let mut listed: Vec<String> = paths.iter().take(MAX_DIFF_PATHS).cloned().collect();
+        if paths.len() > MAX_DIFF_PATHS {
+            listed.push(format!("等 {} 项", paths.len()));
+        }
+        fields.push(format!("settings（{}）", listed.join(", ")));
+    }
+    for (label, a, b) in [
+        ("分类", &now.category, &old.category),
+        ("网站", &now.website_url, &old.website_url),
+        ("备注", &now.notes, &old.notes),
+    ] {
+        if a != b {
+            fields.push(label.to_string());
+        }
+    }
+    if now.is_current != old.is_current {
+        fields.push(if old.is_current { "设为当前" } else { "取消当前" }.to_string());
+    }
+    fields
+}
+
+/// 收集两个 JSON 值之间不同的字段路径（点分形式）
+fn diff_paths(a: &Value, b: &Value, prefix: &str, out: &mut BTreeSet<String>) {
+    match (a, b) {
+        (Value::Object(x), Value::Object(y)) => {
+            for key in x.keys().chain(y.keys()) {
+                let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                match (x.get(key), y.get(key)) {
+                    (Some(u), Some(v)) => diff_paths(u, v, &path, out),
+                    _ => {
+                        out.insert(path);
+                    }
+                }
+            }
+        }
+        _ if a != b => {
+            out.insert(if prefix.is_empty() { "(全部)".to_string() } else { prefix.to_string() });
+        }
+        _ => {}
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{:.1} KiB", bytes as f64 / 1024.0)
+    }
+}
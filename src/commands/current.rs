@@ -0,0 +1,31 @@
+use crate::commands::run::find_provider;
+use crate::db::AppType;
+use crate::error::AppResult;
+use crate::source::ProviderSource;
+
+/// 输出 cc-switch 中标记为当前的配置名称
+pub fn show(source: &dyn ProviderSource, app: AppType) -> AppResult<i32> {
+    match source.get_current(app)? {
+        Some(provider) => {
+            println!("{}", provider.name);
+            Ok(0)
+        }
+        None => {
+            eprintln!("[INFO] cc-switch 中没有标记为当前的 {} 配置", app.label());
+            Ok(1)
+        }
+    }
+}
+
+/// 将指定配置设为 cc-switch 的当前配置（写入数据库）
+pub fn set(source: &dyn ProviderSource, app: AppType, name: &str) -> AppResult<i32> {
+    let db = source.require_database()?;
+    let Some(provider) = find_provider(source, app, name)? else {
+        return Ok(1);
+    };
+
+    db.providers().set_current(app, &provider.id)?;
+    eprintln!("✓ 当前 {} 配置已切换为: {}", app.label(), provider.name);
+    eprintln!("提示: cc-switch 界面需刷新或重启后显示新的当前配置");
+    Ok(0)
+}
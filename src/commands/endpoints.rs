@@ -0,0 +1,55 @@
+use crate::config::cache::CacheManager;
+use crate::config::paths::Paths;
+use crate::config::endpoint::{self, ProbeCache};
+use crate::db::AppType;
+use crate::error::AppResult;
+use crate::commands::run::find_provider;
+use crate::source::ProviderSource;
+
+/// 列出 provider 的候选端点；`probe` 为 true 时并发探测延迟
+pub fn list(
+    source: &dyn ProviderSource,
+    paths: &Paths,
+    app: AppType,
+    name: &str,
+    probe: bool,
+    ttl: u64,
+) -> AppResult<i32> {
+    let Some(provider) = find_provider(source, app, name)? else {
+        return Ok(1);
+    };
+
+    let recorded = match source.database() {
+        Some(db) => db.endpoints().list_urls(app, &provider.id)?,
+        None => Vec::new(),
+    };
+    let urls = endpoint::candidates(&provider, recorded);
+    if urls.is_empty() {
+        eprintln!("[INFO] 配置 '{}' 没有记录端点", provider.name);
+        return Ok(0);
+    }
+
+    let cache = CacheManager::new(paths);
+    let mut probes = ProbeCache::load(cache.probe_cache_path(), ttl);
+    let latencies = if probe {
+        let latencies = probes.probe_all(&urls);
+        probes.save()?;
+        latencies
+    } else {
+        urls.iter().map(|u| probes.get(u).flatten()).collect()
+    };
+
+    let current = endpoint::base_url(&provider);
+    for (i, (url, latency)) in urls.iter().zip(latencies).enumerate() {
+        let mark = if current.as_deref().is_some_and(|c| endpoint::same_url(c, url)) { "*" } else { " " };
+        let latency = match latency {
+            Some(ms) => format!("{} ms", ms),
+            None if probe => "不可达".to_string(),
+            None => "-".to_string(),
+        };
+        println!("{}{:>3}  {:<10}  {}", mark, i + 1, latency, url);
+    }
+    eprintln!("[INFO] * 表示当前配置的端点；启动时使用 --endpoint auto 或 --endpoint <序号> 切换");
+
+    Ok(0)
+}
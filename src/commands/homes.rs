@@ -0,0 +1,26 @@
+use crate::config::file::ConfigFile;
+use crate::config::paths::{expand_tilde, Paths};
+use crate::error::AppResult;
+
+/// 列出配置文件中的命名 home，并输出当前生效的路径
+pub fn list(paths: &Paths) -> AppResult<()> {
+    let homes = ConfigFile::load()?.homes()?;
+    let config_path = ConfigFile::path()?;
+
+    if homes.is_empty() {
+        eprintln!("[INFO] 未配置命名 home（配置文件: {}）", config_path.display());
+        eprintln!("提示: 在配置文件中添加，例如:\n  [homes]\n  work = \"~/work/.cc-switch\"");
+    } else {
+        let width = homes.keys().map(|k| k.chars().count()).max().unwrap_or(0);
+        for (name, entry) in &homes {
+            let mark = if paths.home_name.as_deref() == Some(name.as_str()) { "*" } else { " " };
+            let path = expand_tilde(entry.path())?;
+            println!("{} {:<width$}  {}", mark, name, path.display());
+        }
+    }
+
+    eprintln!("[INFO] 当前 home:  {}", paths.home.display());
+    eprintln!("[INFO] 数据库:     {}", paths.db.display());
+    eprintln!("[INFO] 缓存目录:   {}", paths.cache_dir.display());
+    Ok(())
+}
@@ -1,28 +1,243 @@
-use crate::db::Database;
+use crate::config::{endpoint, redact};
+use crate::db::{AppType, Provider};
 use crate::error::AppResult;
+use crate::source::ProviderSource;
+use clap::Args;
+use std::io::IsTerminal;
+use unicode_width::UnicodeWidthStr;
 
-/// 列出所有可用的配置名称（从 SQLite 查询）
-pub fn list_configs() -> AppResult<()> {
-    let db = Database::open()?;
-    let names = db.providers().list_names()?;
+/// `list` 的输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ListFormat {
+    /// 面向终端：每行一个名称（`--long` 时为表格）
+    #[default]
+    Text,
+    /// JSON 数组
+    Json,
+    /// 制表符分隔，无表头：id、名称、分类、端点主机、token（已遮盖）、网站、备注、是否当前
+    Tsv,
+}
+
+/// `list` 子命令选项
+#[derive(Debug, Clone, Default, Args)]
+pub struct ListOptions {
+    /// 以表格显示分类、端点主机、token（已遮盖）、网站、备注与当前标记
+    #[arg(short, long)]
+    pub long: bool,
+
+    /// 仅列出指定分类的配置（如 official、cn_official、third_party、custom）
+    #[arg(long, value_name = "CATEGORY")]
+    pub category: Option<String>,
+
+    /// 输出格式
+    #[arg(long, value_enum, default_value_t)]
+    pub format: ListFormat,
+}
 
-    if names.is_empty() {
-        eprintln!("错误: 数据库中没有 Claude 配置");
+/// 列出所有可用的配置
+pub fn list_configs(source: &dyn ProviderSource, app: AppType, opts: &ListOptions) -> AppResult<()> {
+    let providers = source.list_all(app)?;
+
+    if providers.is_empty() {
+        eprintln!("错误: 数据库中没有 {} 配置", app.label());
         eprintln!("提示: 请先在 cc-switch 中添加配置");
         std::process::exit(1);
     }
 
-    for name in names {
+    let providers: Vec<Provider> = match &opts.category {
+        Some(category) => providers
+            .into_iter()
+            .filter(|p| {
+                p.category
+                    .as_deref()
+                    .is_some_and(|c| c.eq_ignore_ascii_case(category))
+            })
+            .collect(),
+        None => providers,
+    };
+
+    match opts.format {
+        ListFormat::Json => print_json(&providers)?,
+        ListFormat::Tsv => print_tsv(&providers),
+        ListFormat::Text => {
+            if providers.is_empty() {
+                eprintln!(
+                    "[INFO] 没有分类为 '{}' 的 {} 配置",
+                    opts.category.as_deref().unwrap_or_default(),
+                    app.label()
+                );
+                return Ok(());
+            }
+            if opts.long {
+                print_table(&providers);
+            } else {
+                print_names(&providers);
+            }
+            warn_problems(app, &providers);
+        }
+    }
+
+    Ok(())
+}
+
+/// 每行一个名称（可直接作为 `ccstart <name>` 的参数）
+fn print_names(providers: &[Provider]) {
+    for provider in providers {
+        // 同名配置以 `name#id` 输出，可直接用于启动
+        let name = provider.qualified_name();
         if needs_quoting(&name) {
             println!("\"{}\"", name);
         } else {
             println!("{}", name);
         }
     }
+}
+
+/// 按显示宽度对齐的表格（兼容中文）；stdout 为终端时按 icon_color 为名称着色
+fn print_table(providers: &[Provider]) {
+    let color = std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+    let header = ["", "名称", "分类", "端点", "TOKEN", "网站", "备注"];
+    let rows: Vec<[String; 7]> = providers
+        .iter()
+        .map(|p| {
+            let mark = if p.load_error.is_some() {
+                "!"
+            } else if p.is_current {
+                "*"
+            } else {
+                ""
+            };
+            let [_, category, host, token, website, notes] = fields(p);
+            [
+                mark.to_string(),
+                p.qualified_name().into_owned(),
+                category,
+                host,
+                token,
+                website,
+                notes,
+            ]
+        })
+        .collect();
+
+    let mut widths = header.map(|h| h.width());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.width());
+        }
+    }
+
+    println!("{}", format_row(&header.map(str::to_string), &widths, None));
+    for (row, provider) in rows.iter().zip(providers) {
+        let code = provider.icon_color.as_deref().and_then(ansi_color).filter(|_| color);
+        println!("{}", format_row(row, &widths, code.as_deref()));
+    }
+    eprintln!("[INFO] * 表示 cc-switch 中的当前配置，! 表示 settings_config 无法解析");
+}
+
+/// 拼接一行；`name_color` 为名称列的 ANSI 颜色，按未着色的宽度填充，避免转义序列影响对齐
+fn format_row(cells: &[String], widths: &[usize], name_color: Option<&str>) -> String {
+    let mut line = String::new();
+    for (i, (cell, width)) in cells.iter().zip(widths).enumerate() {
+        if i > 0 {
+            line.push_str("  ");
+        }
+        match name_color {
+            Some(code) if i == 1 => line.push_str(&format!("{}{}\x1b[0m", code, cell)),
+            _ => line.push_str(cell),
+        }
+        if i + 1 < cells.len() {
+            line.extend(std::iter::repeat_n(' ', width - cell.width()));
+        }
+    }
+    line.trim_end().to_string()
+}
+
+/// 制表符分隔，字段中的制表符与换行替换为空格
+fn print_tsv(providers: &[Provider]) {
+    for provider in providers {
+        let [id, category, host, token, website, notes] = fields(provider);
+        let current = if provider.is_current { "1" } else { "0" };
+        let cells = [&id, &provider.name, &category, &host, &token, &website, &notes];
+        let line: Vec<String> = cells.iter().map(|c| c.replace(['\t', '\n', '\r'], " ")).collect();
+        println!("{}\t{}", line.join("\t"), current);
+    }
+}
 
+fn print_json(providers: &[Provider]) -> AppResult<()> {
+    let items: Vec<serde_json::Value> = providers
+        .iter()
+        .map(|p| {
+            serde_json::json!({
+                "id": p.id,
+                "name": p.name,
+                "category": p.category,
+                "baseUrl": endpoint::base_url(p),
+                "token": redact::auth_token(p).map(redact::mask),
+                "websiteUrl": p.website_url,
+                "notes": p.notes,
+                "iconColor": p.icon_color,
+                "current": p.is_current,
+                "duplicate": p.duplicate,
+                "error": p.load_error.as_ref().map(|e| e.to_string()),
+            })
+        })
+        .collect();
+    println!("{}", serde_json::to_string_pretty(&items)?);
     Ok(())
 }
 
+/// 表格与 TSV 共用的展示字段：id、分类、端点主机、遮盖后的 token、网站、备注（单行）
+fn fields(p: &Provider) -> [String; 6] {
+    [
+        p.id.clone(),
+        p.category.clone().unwrap_or_default(),
+        endpoint::base_url(p)
+            .map(|url| endpoint::host(&url).to_string())
+            .unwrap_or_default(),
+        redact::auth_token(p).map(redact::mask).unwrap_or_default(),
+        p.website_url.clone().unwrap_or_default(),
+        p.notes
+            .as_deref()
+            .map(|n| n.split_whitespace().collect::<Vec<_>>().join(" "))
+            .unwrap_or_default(),
+    ]
+}
+
+/// 在 stderr 标注损坏与重名的配置，不影响脚本读取 stdout
+fn warn_problems(app: AppType, providers: &[Provider]) {
+    for provider in providers {
+        if let Some(err) = &provider.load_error {
+            eprintln!(
+                "[WARN] 配置 '{}' 无法使用: settings_config {}",
+                provider.qualified_name(),
+                err
+            );
+        }
+    }
+
+    let duplicates = providers.iter().filter(|p| p.duplicate).count();
+    if duplicates > 0 {
+        eprintln!(
+            "[WARN] 存在 {} 个同名 {} 配置，已附加 id 区分；启动时使用 `name#id` 或 `@id`",
+            duplicates,
+            app.label()
+        );
+    }
+}
+
+/// 将 `#rrggbb` / `#rgb` 转换为 24 位 ANSI 前景色
+fn ansi_color(hex: &str) -> Option<String> {
+    let hex = hex.strip_prefix('#')?;
+    let expanded: String = match hex.len() {
+        3 => hex.chars().flat_map(|c| [c, c]).collect(),
+        6 => hex.to_string(),
+        _ => return None,
+    };
+    let channel = |i: usize| u8::from_str_radix(expanded.get(i..i + 2)?, 16).ok();
+    Some(format!("\x1b[38;2;{};{};{}m", channel(0)?, channel(2)?, channel(4)?))
+}
+
 /// 判断配置名称是否需要双引号包裹
 fn needs_quoting(name: &str) -> bool {
     name.contains(' ')
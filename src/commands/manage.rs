@@ -0,0 +1,358 @@
+use crate::commands::run::find_provider;
+use crate::config::cache::CacheManager;
+use crate::config::common::CommonConfig;
+use crate::config::paths::Paths;
+use crate::db::{validate_settings, AppType, Database, NewProvider, Provider};
+use crate::error::{AppResult, JsonError};
+use crate::source::ProviderSource;
+use anyhow::Context;
+use clap::Args;
+use serde_json::{json, Value};
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// `add` 子命令选项
+#[derive(Debug, Clone, Default, Args)]
+pub struct AddOptions {
+    /// API 地址（写入 ANTHROPIC_BASE_URL / Codex base_url / GOOGLE_GEMINI_BASE_URL）
+    #[arg(long, value_name = "URL")]
+    pub base_url: Option<String>,
+
+    /// 从文件读取完整的 settings_config JSON（`-` 表示标准输入），不再提示输入 token
+    #[arg(long, value_name = "FILE", conflicts_with = "base_url")]
+    pub settings_file: Option<PathBuf>,
+
+    /// 分类（如 official、cn_official、third_party、custom）
+    #[arg(long)]
+    pub category: Option<String>,
+
+    /// 网站地址
+    #[arg(long, value_name = "URL")]
+    pub website_url: Option<String>,
+
+    /// 备注
+    #[arg(long)]
+    pub notes: Option<String>,
+}
+
+/// 新增配置；token 从终端隐藏输入（非终端时从标准输入读取一行）
+pub fn add(
+    source: &dyn ProviderSource,
+    paths: &Paths,
+    app: AppType,
+    name: &str,
+    opts: &AddOptions,
+) -> AppResult<i32> {
+    let db = source.require_database()?;
+
+    let settings_config = match &opts.settings_file {
+        Some(path) => read_settings_file(path)?,
+        None => {
+            let token = read_token(app)?;
+            template(app, opts.base_url.as_deref(), &token)
+        }
+    };
+
+    let new = NewProvider {
+        name: name.to_string(),
+        settings_config,
+        category: opts.category.clone(),
+        website_url: opts.website_url.clone(),
+        notes: opts.notes.clone(),
+        icon_color: None,
+    };
+    let id = db.providers().insert(app, &new)?;
+    eprintln!("✓ 已新增 {} 配置: {}（id: {}）", app.label(), name, id);
+    refresh_cache(db, paths, app, &id)?;
+    Ok(0)
+}
+
+/// 使用 $VISUAL / $EDITOR 编辑配置的 settings_config
+pub fn edit(source: &dyn ProviderSource, paths: &Paths, app: AppType, name: &str) -> AppResult<i32> {
+    let db = source.require_database()?;
+    let Some(provider) = find_provider(source, app, name)? else {
+        return Ok(1);
+    };
+    if let Err(e) = provider.check() {
+        // 损坏的配置无法格式化，提示到 cc-switch 修复
+        eprintln!("错误: {}", e);
+        return Ok(1);
+    }
+
+    let original = serde_json::to_string_pretty(&provider.settings_config)? + "\n";
+    let file = EditFile::create(&provider, &original)?;
+
+    let settings = loop {
+        run_editor(file.path())?;
+        let text = fs::read_to_string(file.path())
+            .with_context(|| format!("读取编辑结果失败: {}", file.path().display()))?;
+        if text == original {
+            eprintln!("[INFO] 内容未修改");
+            return Ok(0);
+        }
+
+        let parsed = serde_json::from_str::<Value>(&text)
+            .map_err(|e| anyhow::Error::new(JsonError::from_serde(&e)))
+            .and_then(|value| validate_settings(app, &value).map(|_| value));
+        match parsed {
+            Ok(value) => break value,
+            Err(e) => {
+                eprintln!("错误: {}", e);
+                if !confirm("重新编辑？")? {
+                    eprintln!("[INFO] 已放弃修改");
+                    return Ok(1);
+                }
+            }
+        }
+    };
+
+    // 编辑期间 cc-switch 可能已修改该配置：在写事务中与编辑前的内容比较，不一致时不覆盖
+    let mut conflict = false;
+    db.providers().modify_settings(app, std::slice::from_ref(&provider.id), |_, current| {
+        conflict = *current != provider.settings_config;
+        Ok((!conflict).then(|| settings.clone()))
+    })?;
+    if conflict {
+        eprintln!("错误: 编辑期间该配置已被其它程序修改，未写入");
+        eprintln!(
+            "提示: 编辑结果保留在 {}，可重新运行 `ccstart edit {}` 后合并",
+            file.keep().display(),
+            provider.qualified_name()
+        );
+        return Ok(1);
+    }
+    eprintln!("✓ 已更新 {} 配置: {}", app.label(), provider.qualified_name());
+    refresh_cache(db, paths, app, &provider.id)?;
+    Ok(0)
+}
+
+/// 重命名配置，并刷新缓存文件
+pub fn rename(
+    source: &dyn ProviderSource,
+    paths: &Paths,
+    app: AppType,
+    name: &str,
+    new_name: &str,
+) -> AppResult<i32> {
+    let db = source.require_database()?;
+    let Some(provider) = find_provider(source, app, name)? else {
+        return Ok(1);
+    };
+
+    db.providers().rename(app, &provider.id, new_name)?;
+    // Codex/Gemini 的目录按 id 命名，不随名称变化；Claude 删除旧名称的 settings 文件
+    if app == AppType::Claude {
+        CacheManager::new(paths).remove_cache(&provider)?;
+    }
+    eprintln!("✓ 已重命名: {} -> {}", provider.qualified_name(), new_name);
+    refresh_cache(db, paths, app, &provider.id)?;
+    Ok(0)
+}
+
+/// 以新名称复制配置（包括分类、备注等所有列）
+pub fn clone(
+    source: &dyn ProviderSource,
+    paths: &Paths,
+    app: AppType,
+    name: &str,
+    new_name: &str,
+) -> AppResult<i32> {
+    let db = source.require_database()?;
+    let Some(provider) = find_provider(source, app, name)? else {
+        return Ok(1);
+    };
+
+    let id = db.providers().clone_provider(app, &provider.id, new_name)?;
+    eprintln!("✓ 已复制: {} -> {}（id: {}）", provider.qualified_name(), new_name, id);
+    refresh_cache(db, paths, app, &id)?;
+    Ok(0)
+}
+
+/// 删除配置及其缓存；当前配置不可删除
+pub fn remove(
+    source: &dyn ProviderSource,
+    paths: &Paths,
+    app: AppType,
+    name: &str,
+    yes: bool,
+) -> AppResult<i32> {
+    let db = source.require_database()?;
+    let Some(provider) = find_provider(source, app, name)? else {
+        return Ok(1);
+    };
+    if provider.is_current {
+        eprintln!("错误: '{}' 是 cc-switch 的当前配置，不能删除", provider.qualified_name());
+        eprintln!("提示: 先使用 `ccstart use <name>` 切换到其它配置");
+        return Ok(1);
+    }
+    if !yes && !io::stdin().is_terminal() {
+        eprintln!("错误: 非交互环境下删除需要 --yes 确认");
+        return Ok(1);
+    }
+    if !yes && !confirm(&format!("确认删除 {} 配置 '{}'？", app.label(), provider.qualified_name()))? {
+        eprintln!("[INFO] 已取消");
+        return Ok(1);
+    }
+
+    db.providers().delete(app, &provider.id)?;
+    let cache = CacheManager::new(paths);
+    cache.remove_cache(&provider)?;
+    eprintln!("✓ 已删除 {} 配置: {}", app.label(), provider.qualified_name());
+    let home = cache.get_cache_path(&provider);
+    if app != AppType::Claude && home.is_dir() {
+        eprintln!("[INFO] {} 的会话等数据保留在: {}", app.program(), home.display());
+    }
+    Ok(0)
+}
+
+/// 写入后按数据库中的最新内容刷新缓存
+pub fn refresh_cache(db: &Database, paths: &Paths, app: AppType, id: &str) -> AppResult<()> {
+    let Some(provider) = db.providers().get_by_id(app, id)? else {
+        return Ok(());
+    };
+    let path = CacheManager::new(paths)
+        .with_common(CommonConfig::load(Some(db), app)?)
+        .force_write(&provider)?;
+    eprintln!("✓ 写入: {} -> {}", provider.qualified_name(), path.display());
+    Ok(())
+}
+
+/// 各应用新建配置时的 settings_config 模板
+fn template(app: AppType, base_url: Option<&str>, token: &str) -> Value {
+    match app {
+        AppType::Claude => {
+            let mut env = json!({ "ANTHROPIC_AUTH_TOKEN": token });
+            if let Some(url) = base_url {
+                env["ANTHROPIC_BASE_URL"] = json!(url);
+            }
+            json!({ "env": env })
+        }
+        AppType::Codex => {
+            // base_url 经 toml_edit 转义，避免引号或反斜杠破坏生成的 TOML
+            let config = match base_url {
+                Some(url) => format!(
+                    "model_provider = \"custom\"\n\n[model_providers.custom]\nname = \"custom\"\nbase_url = {}\nwire_api = \"responses\"\nrequires_openai_auth = true\n",
+                    toml_edit::Value::from(url)
+                ),
+                None => String::new(),
+            };
+            json!({ "auth": { "OPENAI_API_KEY": token }, "config": config })
+        }
+        AppType::Gemini => {
+            let mut env = json!({ "GEMINI_API_KEY": token });
+            if let Some(url) = base_url {
+                env["GOOGLE_GEMINI_BASE_URL"] = json!(url);
+            }
+            json!({ "env": env, "config": {} })
+        }
+    }
+}
+
+/// 读取 token：终端中隐藏输入，否则从标准输入读取一行（便于脚本传入）
+fn read_token(app: AppType) -> AppResult<String> {
+    let token = if io::stdin().is_terminal() {
+        rpassword::prompt_password(format!("请输入 {} token（输入不可见）: ", app.label()))
+            .with_context(|| "读取 token 失败")?
+    } else {
+        let mut line = String::new();
+        io::stdin()
+            .lock()
+            .read_line(&mut line)
+            .with_context(|| "从标准输入读取 token 失败")?;
+        line
+    };
+    let token = token.trim().to_string();
+    if token.is_empty() {
+        eprintln!("[WARN] token 为空，稍后可使用 `ccstart edit` 补充");
+    }
+    Ok(token)
+}
+
+fn read_settings_file(path: &Path) -> AppResult<Value> {
+    let text = if path == Path::new("-") {
+        io::read_to_string(io::stdin()).with_context(|| "从标准输入读取配置失败")?
+    } else {
+        fs::read_to_string(path).with_context(|| format!("读取失败: {}", path.display()))?
+    };
+    serde_json::from_str(&text)
+        .map_err(|e| anyhow::Error::new(JsonError::from_serde(&e)))
+        .with_context(|| format!("解析失败: {}", path.display()))
+}
+
+/// 询问是否继续；非终端环境下视为否
+fn confirm(question: &str) -> AppResult<bool> {
+    if !io::stdin().is_terminal() {
+        eprintln!("{}（非交互环境，视为否）", question);
+        return Ok(false);
+    }
+    eprint!("{} [y/N] ", question);
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+/// 启动编辑器；支持带参数的 $EDITOR（如 `code --wait`）
+fn run_editor(path: &Path) -> AppResult<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| if cfg!(windows) { "notepad" } else { "vi" }.to_string());
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    let status = Command::new(program)
+        .args(parts)
+        .arg(path)
+        .status()
+        .with_context(|| format!("无法启动编辑器: {}", editor))?;
+    if !status.success() {
+        anyhow::bail!("编辑器异常退出: {}", editor);
+    }
+    Ok(())
+}
+
+/// 编辑用的临时文件（仅当前用户可读写，结束后删除）
+struct EditFile {
+    path: PathBuf,
+}
+
+impl EditFile {
+    fn create(provider: &Provider, content: &str) -> AppResult<Self> {
+        let path = std::env::temp_dir().join(format!(
+            "ccstart-{}-{}-{}.json",
+            provider.app_type,
+            provider.id,
+            std::process::id()
+        ));
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options
+            .open(&path)
+            .with_context(|| format!("创建临时文件失败: {}", path.display()))?;
+        file.write_all(content.as_bytes())?;
+        Ok(Self { path })
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 保留临时文件（不再自动删除），返回其路径
+    fn keep(self) -> PathBuf {
+        let path = self.path.clone();
+        std::mem::forget(self);
+        path
+    }
+}
+
+impl Drop for EditFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
@@ -0,0 +1,50 @@
+use crate::config::mcp;
+use crate::error::AppResult;
+use crate::source::ProviderSource;
+
+/// 列出 MCP 服务器，并标记使用给定调整后 `run` 会加载哪些
+pub fn list(source: &dyn ProviderSource, toggles: &[String]) -> AppResult<()> {
+    let db = source.require_database()?;
+    let servers = db.mcp_servers().list_all()?;
+
+    if servers.is_empty() {
+        eprintln!("[INFO] 数据库中没有 MCP 服务器");
+        return Ok(());
+    }
+
+    let selected = mcp::select(&servers, toggles)?;
+    let width = servers.iter().map(|s| s.name.chars().count()).max().unwrap_or(0);
+
+    for server in &servers {
+        let mark = if selected.iter().any(|s| s.name == server.name) { "✓" } else { " " };
+        let detail = match &server.load_error {
+            Some(err) => format!("✗ 配置无法解析（{}）", err),
+            None => describe(&server.server_config),
+        };
+        match &server.description {
+            Some(desc) if !desc.is_empty() => {
+                println!("{} {:<width$}  {}  # {}", mark, server.name, detail, desc)
+            }
+            _ => println!("{} {:<width$}  {}", mark, server.name, detail),
+        }
+    }
+
+    eprintln!("[INFO] ✓ 表示 `ccstart <name>` 启动 Claude 时会加载的服务器");
+    Ok(())
+}
+
+/// 概括服务器的启动方式：stdio 显示命令行，http/sse 显示 URL
+fn describe(config: &serde_json::Value) -> String {
+    if let Some(url) = config.get("url").and_then(|v| v.as_str()) {
+        let kind = config.get("type").and_then(|v| v.as_str()).unwrap_or("http");
+        return format!("[{}] {}", kind, url);
+    }
+    let mut parts = Vec::new();
+    if let Some(cmd) = config.get("command").and_then(|v| v.as_str()) {
+        parts.push(cmd.to_string());
+    }
+    if let Some(args) = config.get("args").and_then(|v| v.as_array()) {
+        parts.extend(args.iter().filter_map(|a| a.as_str()).map(str::to_string));
+    }
+    format!("[stdio] {}", parts.join(" "))
+}
@@ -1,6 +1,20 @@
+pub mod account;
+pub mod auto;
+pub mod backup;
 pub mod completions;
+pub mod current;
+pub mod endpoints;
+pub mod homes;
 pub mod list;
+pub mod manage;
+pub mod mcp;
+pub mod pin;
+pub mod prompts;
 pub mod run;
+pub mod set;
+pub mod show;
+pub mod skills;
+pub mod transfer;
 pub mod update;
 
 // init 模块已废弃（SQLite 模式无需初始化）
@@ -0,0 +1,49 @@
+use crate::commands::run::find_provider;
+use crate::config::mcp;
+use crate::config::pin::{PinFile, PIN_FILES};
+use crate::db::AppType;
+use crate::error::AppResult;
+use crate::source::ProviderSource;
+use anyhow::Context;
+use std::fs;
+
+/// 在当前目录写入绑定文件（`.ccstart.toml`，已有 `.ccstart` 时覆盖该文件）
+pub fn pin(
+    source: &dyn ProviderSource,
+    app: AppType,
+    name: &str,
+    mcp: &[String],
+    args: &[String],
+    force: bool,
+) -> AppResult<i32> {
+    let Some(provider) = find_provider(source, app, name)? else {
+        return Ok(1);
+    };
+    if !mcp.is_empty() {
+        let servers = source.require_database()?.mcp_servers().list_all()?;
+        mcp::select(&servers, mcp)?;
+    }
+
+    let cwd = std::env::current_dir().with_context(|| "无法获取当前工作目录")?;
+    let existing = PIN_FILES.iter().map(|n| cwd.join(n)).find(|p| p.is_file());
+    if let Some(path) = &existing
+        && !force
+    {
+        eprintln!("错误: 目录绑定文件已存在: {}", path.display());
+        eprintln!("提示: 使用 --force 覆盖");
+        return Ok(1);
+    }
+
+    let file = PinFile {
+        provider: provider.qualified_name().into_owned(),
+        app: (app != AppType::Claude).then(|| app.as_str().to_string()),
+        args: args.to_vec(),
+        mcp: mcp.to_vec(),
+        settings: None,
+    };
+    let path = existing.unwrap_or_else(|| cwd.join(PIN_FILES[0]));
+    fs::write(&path, file.to_toml()?).with_context(|| format!("写入失败: {}", path.display()))?;
+    eprintln!("✓ 已绑定 {} 配置: {} ({})", app.label(), file.provider, path.display());
+    eprintln!("提示: 在此目录及子目录中运行 `ccstart` 或 `ccstart .` 即使用该配置");
+    Ok(0)
+}
@@ -0,0 +1,44 @@
+use crate::db::AppType;
+use crate::error::AppResult;
+use crate::source::ProviderSource;
+
+/// 列出指定应用的提示词（✓ 表示默认启用）
+pub fn list(source: &dyn ProviderSource, app: AppType) -> AppResult<()> {
+    let db = source.require_database()?;
+    let prompts = db.prompts().list_all(app)?;
+
+    if prompts.is_empty() {
+        eprintln!("[INFO] 数据库中没有 {} 提示词", app.label());
+        return Ok(());
+    }
+
+    let width = prompts.iter().map(|p| p.name.chars().count()).max().unwrap_or(0);
+    for prompt in &prompts {
+        let mark = if prompt.enabled { "✓" } else { " " };
+        match &prompt.description {
+            Some(desc) if !desc.is_empty() => {
+                println!("{} {:<width$}  # {}", mark, prompt.name, desc)
+            }
+            _ => println!("{} {}", mark, prompt.name),
+        }
+    }
+
+    eprintln!("[INFO] ✓ 表示未指定 --prompt 时默认追加的提示词");
+    Ok(())
+}
+
+/// 输出提示词内容
+pub fn show(source: &dyn ProviderSource, app: AppType, name: &str) -> AppResult<i32> {
+    let db = source.require_database()?;
+    match db.prompts().get_by_name(app, name)? {
+        Some(prompt) => {
+            println!("{}", prompt.content);
+            Ok(0)
+        }
+        None => {
+            eprintln!("错误: 未找到 {} 提示词 '{}'", app.label(), name);
+            eprintln!("提示: 使用 `ccstart prompts list` 查看可用提示词");
+            Ok(1)
+        }
+    }
+}
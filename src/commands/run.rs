@@ -1,64 +1,546 @@
-use crate::config::cache::CacheManager;
-use crate::db::Database;
+use crate::config::accounts::{self, AccountStore};
+use crate::config::cache::{env_pairs, CacheManager};
+use crate::config::claude_home::{self, ClaudeHome};
+use crate::config::common::{merge_json, CommonConfig};
+use crate::config::file::ConfigFile;
+use crate::config::endpoint::{self, ProbeCache};
+use crate::config::mcp;
+use crate::config::paths::Paths;
+use crate::config::pin::{Pin, PinFile};
+use crate::config::skills::SkillResolver;
+use crate::db::{AppType, Database, Provider, Snapshot};
 use crate::error::AppResult;
+use crate::source::ProviderSource;
+use crate::source::resolve::{self, Resolution};
 use anyhow::Context;
+use clap::Args;
+use sha2::{Digest, Sha256};
+use std::fs;
 #[cfg(unix)]
 use std::os::unix::process::ExitStatusExt;
-use std::process::Command;
-
-pub fn run(name: &str, args: &[String]) -> AppResult<i32> {
-    // 1. 打开数据库
-    let db = Database::open()?;
-
-    // 2. 查询 provider
-    let provider = match db.providers().get_by_name(name)? {
-        Some(p) => p,
-        None => {
-            eprintln!("错误: 未找到配置 '{}'", name);
-            if let Ok(names) = db.providers().list_names() {
-                if names.is_empty() {
-                    eprintln!("提示: 数据库中没有 Claude 配置，请先在 cc-switch 中添加。");
-                } else {
-                    eprintln!("提示: 可用配置如下：");
-                    for n in names {
-                        eprintln!("  - {}", n);
-                    }
+use std::process::{Command, ExitStatus};
+
+/// 启动时的附加选项（`run` 子命令与 `ccstart <name>` 共用）
+#[derive(Debug, Clone, Default, Args)]
+pub struct RunOptions {
+    /// 调整本次加载的 MCP 服务器：`+name` 添加、`-name` 移除（可重复，仅 Claude）
+    #[arg(long = "mcp", value_name = "[+|-]NAME", allow_hyphen_values = true)]
+    pub mcp: Vec<String>,
+
+    /// 追加指定的 cc-switch 提示词作为系统提示（可重复；默认使用所有已启用的提示词，仅 Claude）
+    #[arg(long = "prompt", value_name = "NAME")]
+    pub prompts: Vec<String>,
+
+    /// 不追加任何 cc-switch 提示词
+    #[arg(long, conflicts_with = "prompts")]
+    pub no_prompt: bool,
+
+    /// 通过 provider 专属的配置目录（CLAUDE_CONFIG_DIR）加载 cc-switch 中已安装的技能（仅 Claude）
+    #[arg(long)]
+    pub skills: bool,
+
+    /// 使用已保存的 Claude OAuth 账号启动，provider 仅提供非认证设置（见 `ccstart account`，仅 Claude）
+    #[arg(long, value_name = "ACCOUNT", conflicts_with_all = ["endpoint", "isolate", "skills"])]
+    pub account: Option<String>,
+
+    /// 使用 provider 专属的隔离配置目录（CLAUDE_CONFIG_DIR），会话与历史不与其它配置混用（仅 Claude）
+    #[arg(long, conflicts_with = "no_isolate")]
+    pub isolate: bool,
+
+    /// 本次不使用隔离配置目录（覆盖配置文件中的 [isolate]）
+    #[arg(long)]
+    pub no_isolate: bool,
+
+    /// 选择端点：`auto` 并发探测 provider_endpoints 中的地址并使用延迟最低者，或指定序号（见 `ccstart endpoints`）
+    #[arg(long, value_name = "auto|N")]
+    pub endpoint: Option<String>,
+
+    /// 端点探测结果的缓存时间（秒）
+    #[arg(long, value_name = "SECS", env = "CCSTART_PROBE_TTL", default_value_t = endpoint::DEFAULT_PROBE_TTL_SECS)]
+    pub probe_ttl: u64,
+}
+
+pub fn run(
+    source: &dyn ProviderSource,
+    paths: &Paths,
+    app: AppType,
+    name: &str,
+    args: &[String],
+    opts: &RunOptions,
+) -> AppResult<i32> {
+    // `ccstart .`：使用目录绑定文件
+    if name == "." {
+        let Some(pin) = Pin::find_from_cwd()? else {
+            eprintln!("错误: 当前目录及其上级目录中没有 .ccstart / .ccstart.toml");
+            eprintln!("提示: 使用 `ccstart pin <name>` 为当前目录绑定配置");
+            return Ok(1);
+        };
+        if !pin_matches(&pin, app)? {
+            return Ok(1);
+        }
+        eprintln!("[INFO] 目录绑定: {} ({})", pin.file.provider, pin.path.display());
+        return run_preset(source, paths, &pin.file, args, opts);
+    }
+
+    // 1. 查询 provider（启动前的所有读取使用同一读快照）
+    let snapshot = source.snapshot()?;
+    let Some(provider) = find_provider(source, app, name)? else {
+        return Ok(1);
+    };
+
+    launch(source, paths, snapshot, provider, args, opts)
+}
+
+/// 解析用户输入的配置名称（见 [`resolve::resolve`]）；未找到或有歧义时输出提示并返回 None
+pub fn find_provider(
+    source: &dyn ProviderSource,
+    app: AppType,
+    name: &str,
+) -> AppResult<Option<Provider>> {
+    match resolve::resolve(source, app, name)? {
+        Resolution::Found(provider) => {
+            if provider.qualified_name() != name {
+                eprintln!("[INFO] '{}' 匹配到配置: {}", name, provider.qualified_name());
+            }
+            Ok(Some(provider))
+        }
+        Resolution::Ambiguous(candidates) => {
+            eprintln!("错误: '{}' 匹配到多个 {} 配置：", name, app.label());
+            for p in &candidates {
+                eprintln!("  - {}", p.qualified_name());
+            }
+            if candidates.iter().any(|p| p.duplicate) {
+                eprintln!("提示: 同名配置请使用 `name#id` 或 `@id` 指定");
+            } else {
+                eprintln!("提示: 请输入更完整的名称");
+            }
+            Ok(None)
+        }
+        Resolution::NotFound(suggestions) => {
+            eprintln!("错误: 未找到 {} 配置 '{}'", app.label(), name);
+            if !suggestions.is_empty() {
+                eprintln!("提示: 您是否想要：");
+                for n in suggestions {
+                    eprintln!("  - {}", n);
                 }
+            } else if source.list_names(app).is_ok_and(|names| names.is_empty()) {
+                eprintln!(
+                    "提示: 数据库中没有 {} 配置，请先在 cc-switch 中添加。",
+                    app.label()
+                );
+            } else {
+                eprintln!("提示: 使用 `ccstart list` 查看可用配置");
             }
+            Ok(None)
+        }
+    }
+}
+
+/// `ccstart` 无参数时：优先使用目录绑定文件（应用类型须一致），否则使用 cc-switch 中标记为当前的 provider
+pub fn run_current(
+    source: &dyn ProviderSource,
+    paths: &Paths,
+    app: AppType,
+    args: &[String],
+    opts: &RunOptions,
+) -> AppResult<i32> {
+    if let Some(pin) = Pin::find_from_cwd()? {
+        if !pin_matches(&pin, app)? {
             return Ok(1);
         }
+        eprintln!("[INFO] 目录绑定: {} ({})", pin.file.provider, pin.path.display());
+        return run_preset(source, paths, &pin.file, args, opts);
+    }
+
+    let snapshot = source.snapshot()?;
+    let Some(provider) = source.get_current(app)? else {
+        eprintln!("错误: cc-switch 中没有标记为当前的 {} 配置", app.label());
+        eprintln!("提示: 使用 `ccstart <name>` 指定配置，或 `ccstart use <name>` 设置当前配置");
+        return Ok(1);
+    };
+    eprintln!("[INFO] 当前配置: {}", provider.name);
+    launch(source, paths, snapshot, provider, args, opts)
+}
+
+/// 目录绑定的应用类型与 `--app` 不一致时输出提示并返回 false（`ccstart` 与 `ccstart .` 相同）
+fn pin_matches(pin: &Pin, app: AppType) -> AppResult<bool> {
+    let pinned = pin.file.app_type()?;
+    if pinned != app {
+        eprintln!("错误: {} 绑定的是 {} 配置", pin.path.display(), pinned.label());
+        eprintln!(
+            "提示: 使用 `ccstart --app {} .` 启动绑定的配置，或 `ccstart --app {} <name>` 指定配置",
+            pinned, app
+        );
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+/// 使用目录绑定或自动规则给出的设置启动：其中的参数与 MCP 调整排在命令行之前，
+/// settings 覆盖合并到 provider 之上
+pub fn run_preset(
+    source: &dyn ProviderSource,
+    paths: &Paths,
+    file: &PinFile,
+    args: &[String],
+    opts: &RunOptions,
+) -> AppResult<i32> {
+    let snapshot = source.snapshot()?;
+    let Some(mut provider) = find_provider(source, file.app_type()?, &file.provider)? else {
+        return Ok(1);
+    };
+    let mut opts = opts.clone();
+    // 损坏的配置交给 launch 报错，不在其上合并
+    if provider.check().is_ok()
+        && let Some(settings) = file.settings_json()?
+    {
+        // 改名后 [isolate] 无法再按名称匹配，先按原名称判断
+        if provider.app_type == AppType::Claude
+            && !opts.no_isolate
+            && ConfigFile::load_or_default().isolate().enabled_for(&provider)
+        {
+            opts.isolate = true;
+        }
+        merge_json(&mut provider.settings_config, &settings);
+        // Claude 覆盖后的配置使用独立的缓存文件，不改写 provider 本身的缓存；
+        // Codex/Gemini 的目录按 id 命名且保存着会话与登录状态，保持原目录，只改写其中生成的配置
+        if provider.app_type == AppType::Claude {
+            let digest = Sha256::digest(serde_json::to_vec(&settings).with_context(|| "序列化 settings 覆盖失败")?);
+            provider.name = format!("{}+settings-{}", provider.qualified_name(), &format!("{:x}", digest)[..8]);
+            provider.duplicate = false;
+        }
+    }
+
+    opts.mcp = file.mcp.iter().chain(&opts.mcp).cloned().collect();
+    let args: Vec<String> = file.args.iter().chain(args).cloned().collect();
+    launch(source, paths, snapshot, provider, &args, &opts)
+}
+
+/// 落盘 provider 配置并启动对应 CLI
+fn launch(
+    source: &dyn ProviderSource,
+    paths: &Paths,
+    snapshot: Option<Snapshot<'_>>,
+    mut provider: Provider,
+    args: &[String],
+    opts: &RunOptions,
+) -> AppResult<i32> {
+    let app = provider.app_type;
+    // settings_config 损坏时拒绝启动，避免 CLI 以空配置运行
+    if let Err(e) = provider.check() {
+        eprintln!("错误: {}", e);
+        eprintln!("提示: 请在 cc-switch 中修复该配置后重试");
+        return Ok(1);
+    }
+    paths.migrate_legacy_cache();
+
+    // MCP、提示词、技能、端点只存在于 SQLite 数据库中；显式请求这些功能时要求数据库可用
+    let db = source.database();
+    if !opts.mcp.is_empty() || !opts.prompts.is_empty() || opts.skills {
+        source.require_database()?;
+    }
+
+    // 2. 确保缓存文件存在（懒加载 + 哈希比较）
+    let mut cache = CacheManager::new(paths).with_common(CommonConfig::load(db, app)?);
+    let account = opts.account.as_deref().filter(|_| app == AppType::Claude);
+    if let Some(account) = account {
+        if !AccountStore::new(&paths.cache_dir).exists(account) {
+            anyhow::bail!("账号不存在: {}\n提示: 使用 `ccstart account list` 查看已保存的账号", account);
+        }
+        // OAuth 账号模式：去掉 token、端点等认证设置，其余设置（含通用配置）保留
+        provider = accounts::without_auth(&*cache.effective(&provider)?, account);
+        cache = CacheManager::new(paths);
+    }
+    // 先读取启动所需的数据库内容，再释放读快照：端点探测与子进程运行期间不阻塞 cc-switch 写入
+    let claude_db = db.filter(|_| app == AppType::Claude);
+    let servers = match claude_db {
+        Some(db) => db.mcp_servers().list_all()?,
+        None => Vec::new(),
+    };
+    let selected = mcp::select(&servers, &opts.mcp)?;
+    let prompt = match claude_db {
+        Some(db) => resolve_prompt(db, opts)?,
+        None => None,
+    };
+    let skills = if app == AppType::Claude && opts.skills {
+        let resolver = SkillResolver::from_cache(&cache)?;
+        resolver.load(source.require_database()?)?.1
+    } else {
+        Vec::new()
     };
+    let recorded = match (db, &opts.endpoint) {
+        (Some(db), Some(_)) => db.endpoints().list_urls(app, &provider.id)?,
+        _ => Vec::new(),
+    };
+    drop(snapshot);
 
-    // 3. 确保缓存文件存在（懒加载 + 哈希比较）
-    let cache = CacheManager::new()?;
+    if let Some(choice) = &opts.endpoint {
+        apply_endpoint(&cache, &mut provider, recorded, choice, opts.probe_ttl)?;
+    }
     let settings_path = cache.ensure_cached(&provider)?;
 
     eprintln!("[INFO] 使用配置: {}", settings_path.display());
 
-    // 4. 执行 claude
-    let mut cmd = Command::new("claude");
-    cmd.arg("--settings").arg(&settings_path);
-    for a in args {
-        cmd.arg(a);
+    if app != AppType::Claude && !opts.mcp.is_empty() {
+        eprintln!("[WARN] --mcp 仅对 Claude 生效，已忽略");
+    }
+    if app != AppType::Claude && !opts.prompts.is_empty() {
+        eprintln!("[WARN] --prompt 仅对 Claude 生效，已忽略");
+    }
+    if app != AppType::Claude && opts.skills {
+        eprintln!("[WARN] --skills 仅对 Claude 生效，已忽略");
+    }
+    if app != AppType::Claude && opts.account.is_some() {
+        eprintln!("[WARN] --account 仅对 Claude 生效，已忽略");
+    }
+    if app != AppType::Claude && opts.isolate {
+        eprintln!("[WARN] --isolate 仅对 Claude 生效，已忽略");
+    }
+
+    // 3. 执行目标 CLI
+    let mut cmd = Command::new(app.program());
+    match app {
+        AppType::Claude => {
+            cmd.arg("--settings").arg(&settings_path);
+
+            // 根据 cc-switch 中启用的 MCP 服务器生成 --mcp-config
+            if !selected.is_empty() {
+                let mcp_path = cache.ensure_mcp_config(&provider.qualified_name(), &mcp::render(&selected)?)?;
+                let names: Vec<&str> = selected.iter().map(|s| s.name.as_str()).collect();
+                eprintln!("[INFO] 加载 MCP 服务器: {}", names.join(", "));
+                cmd.arg("--mcp-config").arg(mcp_path);
+            }
+
+            // 追加 cc-switch 中的提示词作为系统提示
+            if let Some(prompt) = prompt {
+                cmd.arg("--append-system-prompt").arg(prompt);
+            }
+
+            // 隔离目录与技能叠加目录都通过 CLAUDE_CONFIG_DIR 生效
+            let config = ConfigFile::load_or_default().isolate();
+            let isolate =
+                account.is_none() && !opts.no_isolate && (opts.isolate || config.enabled_for(&provider));
+            let main = claude_home::main_config_dir()?;
+            let home = if let Some(account) = account {
+                let home = ClaudeHome::new(cache.get_account_home_path(account));
+                let store = AccountStore::new(&paths.cache_dir);
+                let _lock = store.lock()?;
+                home.prepare_account(&main, &account_credentials(&store, account)?, store.oauth_account(account).as_ref())?;
+                eprintln!("[INFO] 使用账号: {}（配置目录: {}）", account, home.path().display());
+                Some(home)
+            } else if isolate {
+                let path = cache.get_isolated_home_path(&provider);
+                if let Some(legacy) = cache.migrate_home(&path, &provider)? {
+                    eprintln!("[INFO] 已迁移隔离配置目录: {} -> {}", legacy.display(), path.display());
+                }
+                let home = ClaudeHome::new(path);
+                let shared = home.prepare_isolated(&main, &config.shared(), config.mode)?;
+                eprintln!("[INFO] 隔离配置目录: {}", home.path().display());
+                if !shared.is_empty() {
+                    eprintln!("[INFO] 共享条目: {}", shared.join(", "));
+                }
+                Some(home)
+            } else if opts.skills {
+                let path = cache.get_home_path(&provider);
+                cache.migrate_home(&path, &provider)?;
+                Some(ClaudeHome::new(path))
+            } else {
+                None
+            };
+
+            // 通过配置目录暴露已安装技能
+            if let Some(home) = &home {
+                if opts.skills {
+                    for skill in skills.iter().filter(|s| s.path.is_none()) {
+                        eprintln!("[WARN] 技能未在本地找到，已跳过: {}", skill.key);
+                    }
+                    let linked = if isolate {
+                        home.rebuild_skills(&main.join("skills"), &skills)?
+                    } else {
+                        eprintln!("[INFO] 配置目录: {}", home.path().display());
+                        home.prepare_overlay(&main, &skills)?
+                    };
+                    if !linked.is_empty() {
+                        eprintln!("[INFO] 可用技能: {}", linked.join(", "));
+                    }
+                }
+                cmd.env("CLAUDE_CONFIG_DIR", home.path());
+            }
+        }
+        AppType::Codex => {
+            cmd.env("CODEX_HOME", &settings_path);
+        }
+        AppType::Gemini => {
+            // gemini 只从工作目录或 ~/.gemini 读取 .env，因此直接注入环境变量；
+            // settings.json 通过系统级设置路径加载
+            for (key, value) in env_pairs(&cache.effective(&provider)?.settings_config) {
+                cmd.env(key, value);
+            }
+            cmd.env(
+                "GEMINI_CLI_SYSTEM_SETTINGS_PATH",
+                settings_path.join("settings.json"),
+            );
+        }
+    }
+    cmd.args(args);
+
+    let status = cmd.status().with_context(|| {
+        format!(
+            "执行 '{}' 命令失败，请确认已安装并在 PATH 中",
+            app.program()
+        )
+    })?;
+
+    if let Some(account) = account
+        && let Err(e) = sync_account(paths, &cache, account)
+    {
+        eprintln!("[WARN] 同步账号 '{}' 的凭据失败: {:#}", account, e);
     }
 
-    let status = cmd
-        .status()
-        .with_context(|| "执行 'claude' 命令失败，请确认已安装并在 PATH 中")?;
+    Ok(exit_code(status))
+}
+
+/// 账号的最新凭据：若为 `account use` 的当前账号且主配置目录仍登录着该账号，主配置目录中的凭据更新
+fn account_credentials(store: &AccountStore, account: &str) -> AppResult<Vec<u8>> {
+    let main = accounts::credentials_path()?;
+    if store.active().as_deref() == Some(account)
+        && main.is_file()
+        && store.same_account(account, accounts::read_oauth_account(&accounts::main_state_path()?).as_ref())
+    {
+        return fs::read(&main).with_context(|| format!("读取失败: {}", main.display()));
+    }
+    store.read(account)
+}
+
+/// 会话结束后把 claude 刷新的凭据写回账号存储（当前账号同时写回主配置目录）。
+/// 会话中 `/login` 了其它账号时不写回，避免覆盖原账号
+fn sync_account(paths: &Paths, cache: &CacheManager, account: &str) -> AppResult<()> {
+    let home = cache.get_account_home_path(account);
+    let path = home.join(accounts::CREDENTIALS_FILE);
+    let store = AccountStore::new(&paths.cache_dir);
+    let _lock = store.lock()?;
+    let latest = fs::read(&path).with_context(|| format!("读取失败: {}", path.display()))?;
+    if accounts::check_credentials(&latest).is_err() || store.read(account)? == latest {
+        return Ok(());
+    }
+    let oauth = accounts::read_oauth_account(&home.join(accounts::STATE_FILE));
+    if !store.same_account(account, oauth.as_ref()) {
+        anyhow::bail!(
+            "会话中登录了其它账号（{}），未写回账号 '{}'\n提示: 下次以 --account {} 启动时恢复为原账号的凭据",
+            accounts::describe(oauth.as_ref()),
+            account,
+            account
+        );
+    }
+    store.save(account, &latest, oauth.as_ref())?;
+    let state = accounts::main_state_path()?;
+    if store.active().as_deref() == Some(account)
+        && store.same_account(account, accounts::read_oauth_account(&state).as_ref())
+    {
+        accounts::write_private_atomic(&accounts::credentials_path()?, &latest)?;
+    }
+    Ok(())
+}
+
+/// 按 `--endpoint` 选择端点并写入 provider 配置
+fn apply_endpoint(
+    cache: &CacheManager,
+    provider: &mut Provider,
+    recorded: Vec<String>,
+    choice: &str,
+    ttl: u64,
+) -> AppResult<()> {
+    let urls = endpoint::candidates(provider, recorded);
+    if urls.is_empty() {
+        eprintln!("[WARN] 配置 '{}' 没有可选端点，使用原配置", provider.name);
+        return Ok(());
+    }
+
+    let chosen = if choice.eq_ignore_ascii_case("auto") {
+        let mut probes = ProbeCache::load(cache.probe_cache_path(), ttl);
+        let latencies = probes.probe_all(&urls);
+        probes.save()?;
+        let best = urls
+            .iter()
+            .zip(&latencies)
+            .filter_map(|(url, latency)| latency.map(|ms| (url, ms)))
+            .min_by_key(|(_, ms)| *ms);
+        match best {
+            Some((url, ms)) => {
+                eprintln!("[INFO] 端点探测: {} ({} ms)", url, ms);
+                url.clone()
+            }
+            None => {
+                eprintln!("[WARN] 所有端点均不可达，使用原配置");
+                return Ok(());
+            }
+        }
+    } else {
+        let index: usize = choice
+            .parse()
+            .ok()
+            .filter(|n| (1..=urls.len()).contains(n))
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "无效的端点 '{}'，应为 auto 或 1..={}\n提示: 使用 `ccstart endpoints {}` 查看可选端点",
+                    choice,
+                    urls.len(),
+                    provider.name
+                )
+            })?;
+        urls[index - 1].clone()
+    };
+
+    endpoint::set_base_url(provider, &chosen)
+}
+
+/// 计算要追加的系统提示：显式 `--prompt` 优先，否则使用所有已启用的 Claude 提示词
+fn resolve_prompt(db: &Database, opts: &RunOptions) -> AppResult<Option<String>> {
+    if opts.no_prompt {
+        return Ok(None);
+    }
+
+    let all = db.prompts().list_all(AppType::Claude)?;
+    let chosen: Vec<_> = if opts.prompts.is_empty() {
+        all.iter().filter(|p| p.enabled).collect()
+    } else {
+        let mut chosen = Vec::new();
+        for name in &opts.prompts {
+            let Some(prompt) = all.iter().find(|p| &p.name == name) else {
+                anyhow::bail!(
+                    "未找到 Claude 提示词 '{}'\n提示: 使用 `ccstart prompts list` 查看可用提示词",
+                    name
+                );
+            };
+            chosen.push(prompt);
+        }
+        chosen
+    };
+
+    if chosen.is_empty() {
+        return Ok(None);
+    }
+    let names: Vec<&str> = chosen.iter().map(|p| p.name.as_str()).collect();
+    eprintln!("[INFO] 追加提示词: {}", names.join(", "));
+    let contents: Vec<&str> = chosen.iter().map(|p| p.content.trim()).collect();
+    Ok(Some(contents.join("\n\n")))
+}
 
+/// 将子进程退出状态转换为 ccstart 的退出码
+fn exit_code(status: ExitStatus) -> i32 {
     if let Some(code) = status.code() {
-        Ok(code)
+        code
     } else {
         #[cfg(unix)]
         {
             let sig = status.signal().unwrap_or_default();
             eprintln!("[WARN] 进程被信号终止: {}", sig);
-            Ok(128 + sig)
+            128 + sig
         }
         #[cfg(not(unix))]
         {
             eprintln!("[WARN] 子进程未返回退出码，按失败处理");
-            Ok(1)
+            1
         }
     }
 }
@@ -0,0 +1,191 @@
+use crate::commands::manage::refresh_cache;
+use crate::commands::run::find_provider;
+use crate::config::paths::Paths;
+use crate::config::redact;
+use crate::config::settings_path::{Edit, SettingsPath, ValueKind};
+use crate::db::{AppType, Provider};
+use crate::error::AppResult;
+use crate::source::ProviderSource;
+use clap::Args;
+use serde_json::Value;
+
+/// `set` / `unset` 的目标选择
+#[derive(Debug, Clone, Default, Args)]
+pub struct TargetOptions {
+    /// 修改当前应用的所有配置（此时省略配置名称）
+    #[arg(long, conflicts_with = "category")]
+    pub all: bool,
+
+    /// 修改指定分类的所有配置（此时省略配置名称）
+    #[arg(long, value_name = "CATEGORY")]
+    pub category: Option<String>,
+}
+
+/// `set` 的值类型选项
+#[derive(Debug, Clone, Default, Args)]
+pub struct ValueOptions {
+    /// 值始终作为字符串
+    #[arg(long, conflicts_with = "json")]
+    pub string: bool,
+
+    /// 值必须是合法 JSON（对象、数组、数字等）
+    #[arg(long)]
+    pub json: bool,
+}
+
+impl ValueOptions {
+    fn kind(&self) -> ValueKind {
+        if self.string {
+            ValueKind::String
+        } else if self.json {
+            ValueKind::Json
+        } else {
+            ValueKind::Auto
+        }
+    }
+}
+
+/// 修改 settings_config 中的单个字段：`[NAME] <PATH> <VALUE>` 或 `[NAME] <PATH>(=|+=|-=)<VALUE>`
+pub fn set(
+    source: &dyn ProviderSource,
+    paths: &Paths,
+    app: AppType,
+    args: &[String],
+    target: &TargetOptions,
+    value: &ValueOptions,
+) -> AppResult<i32> {
+    let (name, rest) = split_name(args, target)?;
+    let (path, raw) = match rest {
+        [path] => (path.as_str(), None),
+        [path, raw] => (path.as_str(), Some(raw.as_str())),
+        _ => anyhow::bail!("参数过多: 用法为 `ccstart set <name> <path> <value>` 或 `<path>+=<value>`"),
+    };
+    // 先按空配置解析一次，尽早报告路径或语法错误
+    Edit::parse(path, raw, value.kind(), &Value::Null)?;
+
+    apply(source, paths, app, name, target, |settings| {
+        Edit::parse(path, raw, value.kind(), settings)
+    })
+}
+
+/// 删除 settings_config 中的字段或数组元素
+pub fn unset(
+    source: &dyn ProviderSource,
+    paths: &Paths,
+    app: AppType,
+    args: &[String],
+    target: &TargetOptions,
+) -> AppResult<i32> {
+    let (name, rest) = split_name(args, target)?;
+    let [path] = rest else {
+        anyhow::bail!("参数错误: 用法为 `ccstart unset <name> <path>`");
+    };
+    let path = SettingsPath::parse(path)?;
+
+    apply(source, paths, app, name, target, |_| Ok(Edit::Unset(path.clone())))
+}
+
+/// 按 `--all` / `--category` 区分参数中是否包含配置名称
+fn split_name<'a>(args: &'a [String], target: &TargetOptions) -> AppResult<(Option<&'a str>, &'a [String])> {
+    if target.all || target.category.is_some() {
+        return Ok((None, args));
+    }
+    match args.split_first() {
+        Some((name, rest)) if !rest.is_empty() => Ok((Some(name.as_str()), rest)),
+        _ => anyhow::bail!("缺少配置名称或字段路径（对多个配置修改时使用 --all 或 --category）"),
+    }
+}
+
+/// 对选中的配置逐个应用修改，并在一个事务内读取与写回
+fn apply(
+    source: &dyn ProviderSource,
+    paths: &Paths,
+    app: AppType,
+    name: Option<&str>,
+    target: &TargetOptions,
+    make_edit: impl Fn(&Value) -> AppResult<Edit>,
+) -> AppResult<i32> {
+    let db = source.require_database()?;
+    let Some(providers) = targets(source, app, name, target)? else {
+        return Ok(1);
+    };
+
+    // 在写事务中基于最新内容修改，避免覆盖 cc-switch 界面在此期间的写入
+    let mut ids = Vec::new();
+    for provider in &providers {
+        match provider.check() {
+            Ok(()) => ids.push(provider.id.clone()),
+            Err(e) => eprintln!("✗ 跳过: {}", e),
+        }
+    }
+    let updates = db.providers().modify_settings(app, &ids, |id, current| {
+        let name = providers
+            .iter()
+            .find(|p| p.id == id)
+            .map(|p| p.qualified_name())
+            .unwrap_or_default();
+        let mut settings = current.clone();
+        let edit = make_edit(&settings)?;
+        if edit.apply(&mut settings)? {
+            eprintln!("✓ {}: {}", name, describe(&edit));
+            Ok(Some(settings))
+        } else {
+            eprintln!("· {}: 无变化", name);
+            Ok(None)
+        }
+    })?;
+
+    if updates.is_empty() {
+        return Ok(0);
+    }
+    for (id, _) in &updates {
+        refresh_cache(db, paths, app, id)?;
+    }
+    eprintln!("[INFO] 已更新 {} 个 {} 配置", updates.len(), app.label());
+    Ok(0)
+}
+
+/// 解析要修改的配置；未找到时返回 None（已输出提示）
+fn targets(
+    source: &dyn ProviderSource,
+    app: AppType,
+    name: Option<&str>,
+    target: &TargetOptions,
+) -> AppResult<Option<Vec<Provider>>> {
+    if let Some(name) = name {
+        return Ok(find_provider(source, app, name)?.map(|p| vec![p]));
+    }
+    let providers: Vec<Provider> = source
+        .list_all(app)?
+        .into_iter()
+        .filter(|p| match &target.category {
+            Some(category) => p
+                .category
+                .as_deref()
+                .is_some_and(|c| c.eq_ignore_ascii_case(category)),
+            None => true,
+        })
+        .collect();
+    if providers.is_empty() {
+        match &target.category {
+            Some(category) => eprintln!("错误: 没有分类为 '{}' 的 {} 配置", category, app.label()),
+            None => eprintln!("错误: 数据库中没有 {} 配置", app.label()),
+        }
+        return Ok(None);
+    }
+    Ok(Some(providers))
+}
+
+/// 修改摘要；敏感字段的值会被遮盖
+fn describe(edit: &Edit) -> String {
+    let show = |path: &SettingsPath, value: &Value| match value {
+        Value::String(s) if redact::is_secret_key(path.leaf()) => format!("\"{}\"", redact::mask(s)),
+        other => other.to_string(),
+    };
+    match edit {
+        Edit::Set(path, value) => format!("{} = {}", path, show(path, value)),
+        Edit::Append(path, value) => format!("{} += {}", path, show(path, value)),
+        Edit::Remove(path, value) => format!("{} -= {}", path, show(path, value)),
+        Edit::Unset(path) => format!("删除 {}", path),
+    }
+}
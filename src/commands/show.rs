@@ -0,0 +1,105 @@
+use crate::commands::run::find_provider;
+use crate::config::cache::CacheManager;
+use crate::config::common::CommonConfig;
+use crate::config::file::ConfigFile;
+use crate::config::paths::Paths;
+use crate::config::redact;
+use crate::db::AppType;
+use crate::error::AppResult;
+use crate::source::ProviderSource;
+
+/// `show` 的输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ShowFormat {
+    /// 缓存路径与各缓存文件内容
+    #[default]
+    Pretty,
+    /// 单个 JSON 对象（含 settings_config）
+    Json,
+}
+
+/// 显示启动时交给 CLI 的配置内容；默认遮盖 token/key/secret 等敏感值
+pub fn show(
+    source: &dyn ProviderSource,
+    paths: &Paths,
+    app: AppType,
+    name: &str,
+    format: ShowFormat,
+    reveal: bool,
+) -> AppResult<i32> {
+    let Some(provider) = find_provider(source, app, name)? else {
+        return Ok(1);
+    };
+    if let Err(e) = provider.check() {
+        eprintln!("错误: {}", e);
+        return Ok(1);
+    }
+
+    // 先合并通用配置再遮盖，保证片段中的敏感值同样被遮盖
+    let (mut provider, inherited) = match CommonConfig::load(source.database(), app)? {
+        Some(common) => (common.apply(&provider)?, common.inherited_keys(&provider)?),
+        None => (provider, Vec::new()),
+    };
+    let cache = CacheManager::new(paths);
+    let cache_path = cache.get_cache_path(&provider);
+    // Claude 的隔离配置目录（CLAUDE_CONFIG_DIR）及是否默认启用
+    let isolated = match app {
+        AppType::Claude => Some((
+            cache.get_isolated_home_path(&provider),
+            ConfigFile::load_or_default().isolate().enabled_for(&provider),
+        )),
+        AppType::Codex | AppType::Gemini => None,
+    };
+    if !reveal {
+        redact::redact_provider(&mut provider);
+    }
+
+    match format {
+        ShowFormat::Json => {
+            let out = serde_json::json!({
+                "id": provider.id,
+                "name": provider.name,
+                "app": app.as_str(),
+                "cachePath": cache_path,
+                "cached": cache_path.exists(),
+                "settings": provider.settings_config,
+                "commonConfigKeys": inherited,
+                "isolatedHome": isolated.as_ref().map(|(home, _)| home),
+                "isolated": isolated.as_ref().is_some_and(|(_, enabled)| *enabled),
+            });
+            println!("{}", serde_json::to_string_pretty(&out)?);
+        }
+        ShowFormat::Pretty => {
+            println!("名称: {}", provider.name);
+            println!("ID:   {}", provider.id);
+            if cache_path.exists() {
+                println!("缓存: {}", cache_path.display());
+            } else {
+                println!("缓存: {}（尚未生成，启动或 update 时写入）", cache_path.display());
+            }
+            if !inherited.is_empty() {
+                println!("通用配置: {}", inherited.join(", "));
+            }
+            if let Some((home, enabled)) = &isolated {
+                let state = if *enabled { "已启用" } else { "未启用，可使用 --isolate 或配置 [isolate]" };
+                println!("隔离目录: {}（{}）", home.display(), state);
+            }
+            for (path, content) in cache.render(&provider)? {
+                let label = path.strip_prefix(&cache_path).ok().filter(|p| !p.as_os_str().is_empty());
+                let label = label.unwrap_or(path.file_name().map(AsRef::as_ref).unwrap_or(&path));
+                println!();
+                println!("── {} ──", label.display());
+                let text = String::from_utf8_lossy(&content);
+                println!("{}", text.trim_end());
+            }
+        }
+    }
+
+    if !inherited.is_empty() && format == ShowFormat::Pretty {
+        eprintln!("[INFO] “通用配置”中的字段来自 cc-switch 的通用配置片段（provider 未设置时生效）");
+    }
+    if !reveal {
+        eprintln!("[INFO] 敏感值已遮盖，使用 --reveal 显示完整内容");
+    }
+    Ok(0)
+}
@@ -0,0 +1,66 @@
+use crate::config::cache::CacheManager;
+use crate::config::paths::Paths;
+use crate::config::skills::SkillResolver;
+use crate::error::AppResult;
+use crate::source::ProviderSource;
+use anyhow::Context;
+use std::process::Command;
+
+/// 列出已安装技能的解析结果；`fetch` 为 true 时先克隆缺失的已启用仓库
+pub fn list(source: &dyn ProviderSource, paths: &Paths, fetch: bool) -> AppResult<()> {
+    let db = source.require_database()?;
+    let cache = CacheManager::new(paths);
+    let resolver = SkillResolver::from_cache(&cache)?;
+
+    if fetch {
+        for repo in db.skills().list_repos()?.iter().filter(|r| r.enabled) {
+            let dir = resolver.checkout_dir(repo);
+            if dir.exists() {
+                continue;
+            }
+            let url = format!("https://github.com/{}/{}", repo.owner, repo.name);
+            eprintln!("[INFO] 克隆 {} ({}) -> {}", url, repo.branch, dir.display());
+            let status = Command::new("git")
+                .args(["clone", "--depth", "1", "--branch", &repo.branch, &url])
+                .arg(&dir)
+                .status()
+                .with_context(|| "执行 'git' 命令失败，请确认已安装并在 PATH 中")?;
+            if !status.success() {
+                eprintln!("[WARN] 克隆失败: {}/{}", repo.owner, repo.name);
+            }
+        }
+    }
+
+    let (repos, skills) = resolver.load(db)?;
+
+    if skills.is_empty() {
+        eprintln!("[INFO] 数据库中没有已安装的技能");
+    }
+    let width = skills.iter().map(|s| s.dir_name.chars().count()).max().unwrap_or(0);
+    for skill in &skills {
+        let source = skill.repo.as_deref().unwrap_or("-");
+        match &skill.path {
+            Some(path) => println!("✓ {:<width$}  {}  {}", skill.dir_name, source, path.display()),
+            None => println!("✗ {:<width$}  {}  (本地未找到)", skill.dir_name, source),
+        }
+    }
+
+    let missing: Vec<_> = repos
+        .iter()
+        .filter(|r| r.enabled && !resolver.checkout_dir(r).exists())
+        .collect();
+    if !missing.is_empty() {
+        eprintln!("[INFO] 以下已启用仓库尚未检出（可使用 `ccstart skills --fetch`）：");
+        for repo in missing {
+            eprintln!(
+                "  - {}/{} ({}) -> {}",
+                repo.owner,
+                repo.name,
+                repo.branch,
+                resolver.checkout_dir(repo).display()
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,331 @@
+use crate::commands::manage::refresh_cache;
+use crate::commands::run::find_provider;
+use crate::config::bundle::{self, Bundle, BundleFormat, BundleProvider};
+use crate::config::paths::Paths;
+use crate::config::redact;
+use crate::db::{validate_settings, AppType, ImportItem, NewProvider, Provider};
+use crate::error::AppResult;
+use crate::source::ProviderSource;
+use anyhow::Context;
+use clap::{Args, ValueEnum};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+/// 非交互环境下提供口令的环境变量
+const PASSPHRASE_ENV: &str = "CCSTART_BUNDLE_PASSPHRASE";
+
+/// `export` 子命令选项
+#[derive(Debug, Clone, Default, Args)]
+pub struct ExportOptions {
+    /// 要导出的配置名称（默认导出当前应用的全部配置）
+    #[arg(add = clap_complete::engine::ArgValueCompleter::new(crate::config_name_completer))]
+    pub names: Vec<String>,
+
+    /// 导出所有应用（claude、codex、gemini）的配置
+    #[arg(long, conflicts_with = "names")]
+    pub all_apps: bool,
+
+    /// 输出文件（默认标准输出）
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+
+    /// 文件格式
+    #[arg(long, value_enum, default_value_t)]
+    pub format: BundleFormat,
+
+    /// 使用口令加密（终端中输入口令，或读取 CCSTART_BUNDLE_PASSPHRASE）
+    #[arg(long)]
+    pub encrypt: bool,
+
+    /// 清空 token、key、secret 等敏感值
+    #[arg(long)]
+    pub strip_secrets: bool,
+}
+
+/// 导入时同名配置的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ConflictStrategy {
+    /// 保留已有配置，跳过导入项
+    #[default]
+    Skip,
+    /// 用导入项覆盖已有配置（保留其 id 与当前标记）
+    Overwrite,
+    /// 以新名称导入（如 `name (2)`）
+    Rename,
+}
+
+/// `import` 子命令选项
+#[derive(Debug, Clone, Args)]
+pub struct ImportOptions {
+    /// 导入文件（`-` 表示标准输入）；支持 ccstart 导出格式与旧版 config.json v2
+    #[arg(value_name = "FILE")]
+    pub file: PathBuf,
+
+    /// 与已有配置同名时的处理方式
+    #[arg(long, value_enum, default_value_t)]
+    pub on_conflict: ConflictStrategy,
+
+    /// 仅预览，不写入
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// 不再确认
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+/// 导出配置（含分类、备注与端点）
+pub fn export(source: &dyn ProviderSource, app: AppType, opts: &ExportOptions) -> AppResult<i32> {
+    let mut providers: Vec<Provider> = Vec::new();
+    if opts.names.is_empty() {
+        let apps: &[AppType] = if opts.all_apps {
+            AppType::value_variants()
+        } else {
+            std::slice::from_ref(&app)
+        };
+        for app in apps {
+            providers.extend(source.list_all(*app)?);
+        }
+    } else {
+        for name in &opts.names {
+            let Some(provider) = find_provider(source, app, name)? else {
+                return Ok(1);
+            };
+            providers.push(provider);
+        }
+    }
+
+    let mut bundle = Bundle::default();
+    for provider in &providers {
+        if let Err(e) = provider.check() {
+            eprintln!("✗ 跳过: {}", e);
+            continue;
+        }
+        let endpoints = match source.database() {
+            Some(db) if db.capabilities()?.table_available("provider_endpoints")? => {
+                Some(db.endpoints().list_urls(provider.app_type, &provider.id)?)
+            }
+            _ => None,
+        };
+        let mut item = BundleProvider::from_provider(provider, endpoints);
+        if opts.strip_secrets {
+            redact::strip_secrets(provider.app_type, &mut item.settings_config);
+        }
+        bundle.providers.push(item);
+    }
+    if bundle.providers.is_empty() {
+        eprintln!("错误: 没有可导出的配置");
+        return Ok(1);
+    }
+
+    let mut doc = bundle.to_document(opts.format);
+    if opts.encrypt {
+        let passphrase = read_passphrase(true)?;
+        doc = bundle::encrypt(&doc, &passphrase)?;
+    }
+    let text = serde_json::to_string_pretty(&doc)? + "\n";
+
+    match &opts.output {
+        Some(path) if path != Path::new("-") => {
+            write_private(path, text.as_bytes())?;
+            eprintln!("✓ 已导出 {} 个配置 -> {}", bundle.providers.len(), path.display());
+        }
+        _ => {
+            io::stdout().write_all(text.as_bytes())?;
+            eprintln!("[INFO] 已导出 {} 个配置", bundle.providers.len());
+        }
+    }
+    if !opts.strip_secrets && !opts.encrypt {
+        eprintln!("[WARN] 导出内容包含 token 等敏感信息，可使用 --encrypt 或 --strip-secrets");
+    }
+    Ok(0)
+}
+
+/// 计划中的单项导入
+struct Planned {
+    item: ImportItem,
+    source_name: String,
+}
+
+/// 导入配置：先预览，再在一个事务内写入
+pub fn import(source: &dyn ProviderSource, paths: &Paths, opts: &ImportOptions) -> AppResult<i32> {
+    let db = source.require_database()?;
+    let bundle = read_bundle(&opts.file)?;
+    if bundle.providers.is_empty() {
+        eprintln!("[INFO] 文件中没有配置");
+        return Ok(0);
+    }
+
+    let mut plan: Vec<Planned> = Vec::new();
+    let mut skipped = 0;
+    for app in AppType::value_variants() {
+        let existing = db.providers().list_all(*app)?;
+        let mut names: HashSet<String> = existing.iter().map(|p| p.name.clone()).collect();
+        let mut ids: HashSet<String> = existing.iter().map(|p| p.id.clone()).collect();
+        let mut overwritten: HashSet<String> = HashSet::new();
+
+        for incoming in &bundle.providers {
+            if incoming.app_type()? != *app {
+                continue;
+            }
+            let label = format!("{}/{}", app, incoming.name);
+            if let Err(e) = validate_settings(*app, &incoming.settings_config) {
+                println!("✗ {}（{}）", label, e);
+                skipped += 1;
+                continue;
+            }
+
+            let conflicts: Vec<&Provider> = existing.iter().filter(|p| p.name == incoming.name).collect();
+            let mut name = incoming.name.clone();
+            let mut target = None;
+            if names.contains(&name) {
+                match opts.on_conflict {
+                    ConflictStrategy::Skip => {
+                        println!("= {}（已存在，跳过）", label);
+                        skipped += 1;
+                        continue;
+                    }
+                    ConflictStrategy::Overwrite => match conflicts.as_slice() {
+                        [existing] if overwritten.insert(existing.id.clone()) => target = Some(*existing),
+                        _ => {
+                            println!("✗ {}（存在多个同名配置或已在本次导入中使用，无法覆盖）", label);
+                            skipped += 1;
+                            continue;
+                        }
+                    },
+                    ConflictStrategy::Rename => {
+                        name = (2..)
+                            .map(|n| format!("{} ({})", incoming.name, n))
+                            .find(|candidate| !names.contains(candidate))
+                            .unwrap_or_default();
+                        println!("+ {} -> {}", label, name);
+                    }
+                }
+            } else {
+                println!("+ {}", label);
+            }
+
+            let mut settings_config = incoming.settings_config.clone();
+            if let Some(target) = target {
+                // --strip-secrets 导出的文件不含密钥，覆盖时沿用现有 token
+                let kept = redact::keep_secrets(*app, &mut settings_config, &target.settings_config);
+                let note = if kept { "，保留现有密钥" } else { "" };
+                println!("~ {}（覆盖 id: {}{}）", label, target.id, note);
+            }
+
+            let overwrite = target.is_some();
+            let id = match target {
+                Some(target) => target.id.clone(),
+                None if !incoming.id.is_empty() && !ids.contains(&incoming.id) => incoming.id.clone(),
+                None => uuid::Uuid::new_v4().to_string(),
+            };
+            names.insert(name.clone());
+            ids.insert(id.clone());
+            plan.push(Planned {
+                source_name: incoming.name.clone(),
+                item: ImportItem {
+                    app: *app,
+                    overwrite,
+                    id,
+                    endpoints: incoming.endpoints.clone(),
+                    provider: NewProvider {
+                        name,
+                        settings_config,
+                        category: incoming.category.clone(),
+                        website_url: incoming.website_url.clone(),
+                        notes: incoming.notes.clone(),
+                        icon_color: incoming.icon_color.clone(),
+                    },
+                },
+            });
+        }
+    }
+
+    eprintln!(
+        "[INFO] 将导入 {} 个配置，跳过 {} 个（+ 新增，~ 覆盖，= 跳过，✗ 无效）",
+        plan.len(),
+        skipped
+    );
+    if opts.dry_run || plan.is_empty() {
+        return Ok(0);
+    }
+    if !opts.yes {
+        if !io::stdin().is_terminal() {
+            eprintln!("错误: 非交互环境下导入需要 --yes 确认（或使用 --dry-run 预览）");
+            return Ok(1);
+        }
+        eprint!("确认导入？[y/N] ");
+        io::stderr().flush()?;
+        let mut answer = String::new();
+        io::stdin().lock().read_line(&mut answer)?;
+        if !matches!(answer.trim(), "y" | "Y" | "yes") {
+            eprintln!("[INFO] 已取消");
+            return Ok(1);
+        }
+    }
+
+    let items: Vec<ImportItem> = plan.iter().map(|p| p.item.clone()).collect();
+    db.providers().import(&items)?;
+    for planned in &plan {
+        if let Err(e) = refresh_cache(db, paths, planned.item.app, &planned.item.id) {
+            eprintln!("[WARN] 刷新 '{}' 的缓存失败: {}", planned.source_name, e);
+        }
+    }
+    eprintln!("✓ 已导入 {} 个配置", plan.len());
+    Ok(0)
+}
+
+/// 读取并（必要时）解密导入文件
+fn read_bundle(path: &Path) -> AppResult<Bundle> {
+    let text = if path == Path::new("-") {
+        io::read_to_string(io::stdin()).with_context(|| "从标准输入读取失败")?
+    } else {
+        fs::read_to_string(path).with_context(|| format!("读取失败: {}", path.display()))?
+    };
+    let mut doc: Value =
+        serde_json::from_str(&text).with_context(|| format!("解析失败: {}", path.display()))?;
+    if bundle::is_encrypted(&doc) {
+        let passphrase = read_passphrase(false)?;
+        doc = bundle::decrypt(&doc, &passphrase)?;
+    }
+    Bundle::from_document(&doc, path)
+}
+
+/// 读取口令：优先环境变量，其次终端隐藏输入（加密时需输入两次确认）
+fn read_passphrase(confirm: bool) -> AppResult<String> {
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV) {
+        return Ok(passphrase);
+    }
+    if !io::stdin().is_terminal() {
+        anyhow::bail!("需要口令: 请在终端中运行，或设置环境变量 {}", PASSPHRASE_ENV);
+    }
+    let passphrase = rpassword::prompt_password("请输入口令: ").with_context(|| "读取口令失败")?;
+    if passphrase.is_empty() {
+        anyhow::bail!("口令不能为空");
+    }
+    if confirm {
+        let again = rpassword::prompt_password("请再次输入口令: ").with_context(|| "读取口令失败")?;
+        if again != passphrase {
+            anyhow::bail!("两次输入的口令不一致");
+        }
+    }
+    Ok(passphrase)
+}
+
+/// 写入仅当前用户可读写的文件（导出内容可能包含密钥）
+fn write_private(path: &Path, content: &[u8]) -> AppResult<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(content))
+        .with_context(|| format!("写入失败: {}", path.display()))
+}
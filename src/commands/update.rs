@@ -1,35 +1,43 @@
 use crate::config::cache::CacheManager;
-use crate::db::Database;
+use crate::config::common::CommonConfig;
+use crate::config::paths::Paths;
+use crate::db::AppType;
 use crate::error::AppResult;
+use crate::source::ProviderSource;
 
 /// 更新配置：强制刷新所有缓存文件
-pub fn run() -> AppResult<()> {
-    eprintln!("[INFO] 正在从数据库同步配置...");
+pub fn run(source: &dyn ProviderSource, paths: &Paths, app: AppType) -> AppResult<()> {
+    eprintln!("[INFO] 正在从 {} 同步配置...", source.describe());
+    paths.migrate_legacy_cache();
 
-    // 1. 打开数据库
-    let db = Database::open()?;
-
-    // 2. 获取所有 provider
-    let providers = db.providers().list_all()?;
-    let valid_names: Vec<String> = providers.iter().map(|p| p.name.clone()).collect();
+    // 1. 获取所有 provider（整个同步过程使用同一读快照）
+    let _snapshot = source.snapshot()?;
+    let providers = source.list_all(app)?;
 
     if providers.is_empty() {
-        eprintln!("[WARN] 数据库中没有 Claude 配置");
+        eprintln!("[WARN] 数据库中没有 {} 配置", app.label());
         return Ok(());
     }
 
-    // 3. 强制写入所有缓存
-    let cache = CacheManager::new()?;
+    // 2. 强制写入所有缓存
+    let cache = CacheManager::new(paths).with_common(CommonConfig::load(source.database(), app)?);
     let mut write_count = 0;
+    let mut broken = 0;
 
     for provider in &providers {
+        // settings_config 损坏的配置不写入缓存，保留原有缓存文件
+        if let Err(e) = provider.check() {
+            eprintln!("✗ 跳过: {}", e);
+            broken += 1;
+            continue;
+        }
         let path = cache.force_write(provider)?;
-        eprintln!("✓ 写入: {} -> {}", provider.name, path.display());
+        eprintln!("✓ 写入: {} -> {}", provider.qualified_name(), path.display());
         write_count += 1;
     }
 
-    // 4. 清理过期缓存
-    let removed = cache.cleanup_stale(&valid_names)?;
+    // 3. 清理过期缓存
+    let removed = cache.cleanup_stale(app, &providers)?;
     for name in &removed {
         eprintln!("✓ 删除: {}", name);
     }
@@ -39,6 +47,9 @@ pub fn run() -> AppResult<()> {
         write_count,
         removed.len()
     );
+    if broken > 0 {
+        eprintln!("[WARN] {} 个配置的 settings_config 无法解析，请在 cc-switch 中修复", broken);
+    }
 
     Ok(())
 }
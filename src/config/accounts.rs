@@ -0,0 +1,314 @@
+use crate::config::claude_home;
+use crate::db::Provider;
+use crate::error::AppResult;
+use crate::utils::encoding::{decode_config_name, encode_config_name};
+use anyhow::Context;
+use serde_json::Value;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Claude OAuth 凭据文件（位于主配置目录）
+pub const CREDENTIALS_FILE: &str = ".credentials.json";
+
+/// claude 的状态文件，其中 `oauthAccount` 记录当前登录的账号
+pub const STATE_FILE: &str = ".claude.json";
+
+/// 记录 `account use` 当前账号的文件
+const ACTIVE_FILE: &str = "active";
+const LOCK_FILE: &str = ".lock";
+/// 账号身份（`oauthAccount`）的附属文件后缀
+const OAUTH_SUFFIX: &str = ".oauth";
+
+/// OAuth 模式下从 provider 中去掉的认证与端点相关环境变量
+const AUTH_ENV_KEYS: &[&str] = &["ANTHROPIC_AUTH_TOKEN", "ANTHROPIC_API_KEY", "ANTHROPIC_BASE_URL"];
+
+/// 主配置目录中的凭据文件
+pub fn credentials_path() -> AppResult<PathBuf> {
+    Ok(claude_home::main_config_dir()?.join(CREDENTIALS_FILE))
+}
+
+/// 主配置目录对应的状态文件：设置 `CLAUDE_CONFIG_DIR` 时位于该目录下，否则为 `~/.claude.json`
+pub fn main_state_path() -> AppResult<PathBuf> {
+    match claude_home::main_config_file() {
+        Some(path) => Ok(path),
+        None => Ok(claude_home::main_config_dir()?.join(STATE_FILE)),
+    }
+}
+
+/// 读取状态文件中的 `oauthAccount`（不存在或无法解析时为 None）
+pub fn read_oauth_account(state: &Path) -> Option<Value> {
+    let raw = fs::read(state).ok()?;
+    let value: Value = serde_json::from_slice(&raw).ok()?;
+    value.get("oauthAccount").filter(|v| v.is_object()).cloned()
+}
+
+/// 将 `oauthAccount` 写入状态文件，保留其余字段
+pub fn write_oauth_account(state: &Path, account: &Value) -> AppResult<()> {
+    let mut value: Value = match fs::read(state) {
+        Ok(raw) => serde_json::from_slice(&raw).with_context(|| format!("无法解析: {}", state.display()))?,
+        Err(_) => Value::Object(Default::default()),
+    };
+    let Some(object) = value.as_object_mut() else {
+        anyhow::bail!("状态文件不是 JSON 对象: {}", state.display());
+    };
+    object.insert("oauthAccount".to_string(), account.clone());
+    write_private_atomic(state, &serde_json::to_vec_pretty(&value)?)
+}
+
+/// 账号身份：`accountUuid`，缺失时使用邮箱
+fn identity(account: &Value) -> Option<&str> {
+    account
+        .get("accountUuid")
+        .or_else(|| account.get("emailAddress"))
+        .and_then(Value::as_str)
+        .filter(|s| !s.is_empty())
+}
+
+/// 面向用户的账号描述（邮箱优先）
+pub fn describe(account: Option<&Value>) -> String {
+    account
+        .and_then(|a| a.get("emailAddress").or_else(|| a.get("accountUuid")))
+        .and_then(Value::as_str)
+        .unwrap_or("未知账号")
+        .to_string()
+}
+
+/// 已保存的账号
+#[derive(Debug, Clone)]
+pub struct AccountInfo {
+    pub name: String,
+    /// 保存时登录的邮箱
+    pub email: Option<String>,
+    /// 订阅类型（`claudeAiOauth.subscriptionType`）
+    pub subscription: Option<String>,
+    /// access token 过期时间（毫秒时间戳）
+    pub expires_at: Option<i64>,
+    pub active: bool,
+}
+
+/// 账号凭据存储：`<缓存目录>/accounts/`，目录 0700、文件 0600
+pub struct AccountStore {
+    dir: PathBuf,
+}
+
+/// 存储锁；drop 时释放
+pub struct AccountLock {
+    _file: fs::File,
+}
+
+impl AccountStore {
+    pub fn new(cache_dir: &Path) -> Self {
+        Self {
+            dir: cache_dir.join("accounts"),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 获取排它锁：同一时间只有一个 ccstart 进程读写凭据
+    pub fn lock(&self) -> AppResult<AccountLock> {
+        self.ensure_dir()?;
+        let path = self.dir.join(LOCK_FILE);
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .with_context(|| format!("无法打开锁文件: {}", path.display()))?;
+        file.lock().with_context(|| format!("获取锁失败: {}", path.display()))?;
+        Ok(AccountLock { _file: file })
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", encode_config_name(name)))
+    }
+
+    pub fn exists(&self, name: &str) -> bool {
+        self.path(name).is_file()
+    }
+
+    /// 读取账号凭据
+    pub fn read(&self, name: &str) -> AppResult<Vec<u8>> {
+        let path = self.path(name);
+        if !path.is_file() {
+            anyhow::bail!("账号不存在: {}\n提示: 使用 `ccstart account list` 查看已保存的账号", name);
+        }
+        fs::read(&path).with_context(|| format!("读取失败: {}", path.display()))
+    }
+
+    fn oauth_path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}{}", encode_config_name(name), OAUTH_SUFFIX))
+    }
+
+    /// 保存账号凭据及其身份（`oauthAccount`）；身份未知时保留已记录的身份
+    pub fn save(&self, name: &str, credentials: &[u8], oauth: Option<&Value>) -> AppResult<()> {
+        self.ensure_dir()?;
+        write_private_atomic(&self.path(name), credentials)?;
+        if let Some(oauth) = oauth {
+            write_private_atomic(&self.oauth_path(name), &serde_json::to_vec_pretty(oauth)?)?;
+        }
+        Ok(())
+    }
+
+    /// 保存时记录的 `oauthAccount`
+    pub fn oauth_account(&self, name: &str) -> Option<Value> {
+        let raw = fs::read(self.oauth_path(name)).ok()?;
+        serde_json::from_slice(&raw).ok()
+    }
+
+    /// `current` 是否为账号 `name` 的登录：两者身份都已知且不同时为 false。
+    /// 用于区分 token 刷新与在 claude 中 `/login` 了另一个账号
+    pub fn same_account(&self, name: &str, current: Option<&Value>) -> bool {
+        let saved = self.oauth_account(name);
+        match (saved.as_ref().and_then(identity), current.and_then(identity)) {
+            (Some(saved), Some(current)) => saved == current,
+            _ => true,
+        }
+    }
+
+    pub fn remove(&self, name: &str) -> AppResult<()> {
+        let path = self.path(name);
+        fs::remove_file(&path).with_context(|| format!("删除失败: {}", path.display()))?;
+        let oauth = self.oauth_path(name);
+        if oauth.exists() {
+            fs::remove_file(&oauth).with_context(|| format!("删除失败: {}", oauth.display()))?;
+        }
+        if self.active().as_deref() == Some(name) {
+            self.set_active(None)?;
+        }
+        Ok(())
+    }
+
+    /// `account use` 切换到的账号
+    pub fn active(&self) -> Option<String> {
+        fs::read_to_string(self.dir.join(ACTIVE_FILE))
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    }
+
+    pub fn set_active(&self, name: Option<&str>) -> AppResult<()> {
+        self.ensure_dir()?;
+        let path = self.dir.join(ACTIVE_FILE);
+        match name {
+            Some(name) => write_private_atomic(&path, name.as_bytes()),
+            None if path.exists() => {
+                fs::remove_file(&path).with_context(|| format!("删除失败: {}", path.display()))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// 列出已保存的账号（按名称排序）
+    pub fn list(&self) -> AppResult<Vec<AccountInfo>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let active = self.active();
+        let mut accounts = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let Some(encoded) = file_name.strip_suffix(".json") else {
+                continue;
+            };
+            let Ok(name) = decode_config_name(encoded) else {
+                continue;
+            };
+            let oauth = fs::read(entry.path())
+                .ok()
+                .and_then(|raw| serde_json::from_slice::<Value>(&raw).ok())
+                .and_then(|v| v.get("claudeAiOauth").cloned())
+                .unwrap_or(Value::Null);
+            let email = self
+                .oauth_account(&name)
+                .and_then(|a| a.get("emailAddress").and_then(Value::as_str).map(str::to_string));
+            accounts.push(AccountInfo {
+                email,
+                subscription: oauth.get("subscriptionType").and_then(Value::as_str).map(str::to_string),
+                expires_at: oauth.get("expiresAt").and_then(Value::as_i64),
+                active: active.as_deref() == Some(name.as_str()),
+                name,
+            });
+        }
+        accounts.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(accounts)
+    }
+
+    /// 与 `credentials` 内容相同的已保存账号
+    pub fn find_matching(&self, credentials: &[u8]) -> AppResult<Option<String>> {
+        for account in self.list()? {
+            if self.read(&account.name)? == credentials {
+                return Ok(Some(account.name));
+            }
+        }
+        Ok(None)
+    }
+
+    fn ensure_dir(&self) -> AppResult<()> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("创建目录失败: {}", self.dir.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&self.dir, fs::Permissions::from_mode(0o700))?;
+        }
+        Ok(())
+    }
+}
+
+/// 检查凭据文件是否为 Claude OAuth 凭据
+pub fn check_credentials(raw: &[u8]) -> AppResult<()> {
+    let value: Value = serde_json::from_slice(raw).with_context(|| "凭据文件不是合法的 JSON")?;
+    if value.get("claudeAiOauth").is_none_or(|v| !v.is_object()) {
+        anyhow::bail!("凭据文件中没有 claudeAiOauth（请先在 claude 中使用 /login 登录订阅账号）");
+    }
+    Ok(())
+}
+
+/// OAuth 账号模式使用的 provider：去掉 token、API key、端点与 apiKeyHelper，保留其余设置。
+/// 名称附加 `+account`，缓存文件与普通启动互不覆盖
+pub fn without_auth(provider: &Provider, account: &str) -> Provider {
+    let mut result = provider.clone();
+    result.name = format!("{}+{}", provider.qualified_name(), account);
+    result.duplicate = false;
+    if let Some(settings) = result.settings_config.as_object_mut() {
+        settings.remove("apiKeyHelper");
+        if let Some(Value::Object(env)) = settings.get_mut("env") {
+            for key in AUTH_ENV_KEYS {
+                env.remove(*key);
+            }
+        }
+    }
+    result
+}
+
+/// 先写临时文件（0600）再改名，读者不会看到写了一半的凭据
+pub fn write_private_atomic(path: &Path, content: &[u8]) -> AppResult<()> {
+    let tmp = path.with_file_name(format!(
+        ".{}.{}.tmp",
+        path.file_name().unwrap_or_default().to_string_lossy(),
+        std::process::id()
+    ));
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let result = options
+        .open(&tmp)
+        .and_then(|mut file| {
+            file.write_all(content)?;
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&tmp, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result.with_context(|| format!("写入失败: {}", path.display()))
+}
@@ -0,0 +1,307 @@
+use crate::db::{AppType, Provider};
+use crate::error::AppResult;
+use crate::source::{LegacyJsonSource, ProviderSource};
+use anyhow::Context;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::path::Path;
+
+/// 导出文件的类型标识
+const BUNDLE_KIND: &str = "ccstart-bundle";
+
+/// 当前导出格式版本；读取更高版本时报错
+pub const BUNDLE_VERSION: u32 = 1;
+
+const KDF: &str = "argon2id";
+const CIPHER: &str = "chacha20-poly1305";
+
+/// 导出文件格式（`--format`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum BundleFormat {
+    /// ccstart 导出格式（含端点）
+    #[default]
+    Bundle,
+    /// 旧版 cc-switch 的 config.json（version 2）
+    Legacy,
+}
+
+/// 导出文件中的单个 provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleProvider {
+    pub app: String,
+    pub id: String,
+    pub name: String,
+    pub settings_config: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub website_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon_color: Option<String>,
+    /// 端点列表；None 表示导出时没有端点信息（旧版文件或无端点表），导入时不改动现有端点
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoints: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub current: bool,
+}
+
+impl BundleProvider {
+    pub fn from_provider(provider: &Provider, endpoints: Option<Vec<String>>) -> Self {
+        Self {
+            app: provider.app_type.as_str().to_string(),
+            id: provider.id.clone(),
+            name: provider.name.clone(),
+            settings_config: provider.settings_config.clone(),
+            category: provider.category.clone(),
+            website_url: provider.website_url.clone(),
+            notes: provider.notes.clone(),
+            icon_color: provider.icon_color.clone(),
+            endpoints,
+            current: provider.is_current,
+        }
+    }
+
+    pub fn app_type(&self) -> AppResult<AppType> {
+        AppType::from_str(&self.app, true)
+            .map_err(|_| anyhow::anyhow!("不支持的应用类型 '{}'（provider '{}'）", self.app, self.name))
+    }
+}
+
+/// 一组待导出或已读取的 provider
+#[derive(Debug, Clone, Default)]
+pub struct Bundle {
+    pub providers: Vec<BundleProvider>,
+}
+
+impl Bundle {
+    /// 序列化为指定格式的文档
+    pub fn to_document(&self, format: BundleFormat) -> Value {
+        match format {
+            BundleFormat::Bundle => json!({
+                "kind": BUNDLE_KIND,
+                "version": BUNDLE_VERSION,
+                "providers": self.providers,
+            }),
+            BundleFormat::Legacy => {
+                let mut root = Map::new();
+                root.insert("version".into(), json!(2));
+                for app in AppType::value_variants() {
+                    let mut providers = Map::new();
+                    let mut current = None;
+                    for (index, p) in self.providers.iter().filter(|p| p.app == app.as_str()).enumerate() {
+                        let mut entry = json!({
+                            "id": p.id,
+                            "name": p.name,
+                            "settingsConfig": p.settings_config,
+                            "sortIndex": index,
+                        });
+                        for (key, value) in [
+                            ("category", &p.category),
+                            ("websiteUrl", &p.website_url),
+                            ("notes", &p.notes),
+                            ("iconColor", &p.icon_color),
+                        ] {
+                            if let Some(value) = value {
+                                entry[key] = json!(value);
+                            }
+                        }
+                        if p.current {
+                            current = Some(p.id.clone());
+                        }
+                        providers.insert(p.id.clone(), entry);
+                    }
+                    if !providers.is_empty() {
+                        root.insert(
+                            app.as_str().into(),
+                            json!({ "providers": providers, "current": current.unwrap_or_default() }),
+                        );
+                    }
+                }
+                Value::Object(root)
+            }
+        }
+    }
+
+    /// 解析文档，自动识别 ccstart 导出格式与旧版 config.json v2
+    pub fn from_document(doc: &Value, label: &Path) -> AppResult<Self> {
+        if doc.get("kind").and_then(Value::as_str) == Some(BUNDLE_KIND) {
+            check_version(doc)?;
+            let providers = serde_json::from_value(doc.get("providers").cloned().unwrap_or(json!([])))
+                .with_context(|| format!("解析导出文件失败: {}", label.display()))?;
+            return Ok(Self { providers });
+        }
+
+        // 旧版 config.json：复用旧版来源的解析与排序
+        let legacy = LegacyJsonSource::from_value(label, doc)?;
+        let mut providers = Vec::new();
+        for app in AppType::value_variants() {
+            for p in legacy.list_all(*app)? {
+                providers.push(BundleProvider::from_provider(&p, None));
+            }
+        }
+        Ok(Self { providers })
+    }
+}
+
+fn check_version(doc: &Value) -> AppResult<()> {
+    let version = doc.get("version").and_then(Value::as_u64).unwrap_or(0);
+    if version > u64::from(BUNDLE_VERSION) {
+        anyhow::bail!(
+            "导出文件版本过新: v{}（ccstart 支持至 v{}）\n提示: 请升级 ccstart",
+            version,
+            BUNDLE_VERSION
+        );
+    }
+    Ok(())
+}
+
+/// 文档是否为加密封装
+pub fn is_encrypted(doc: &Value) -> bool {
+    doc.get("encryption").is_some()
+}
+
+/// 用口令加密文档：argon2id 派生密钥，ChaCha20-Poly1305 加密
+pub fn encrypt(doc: &Value, passphrase: &str) -> AppResult<Value> {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let plaintext = serde_json::to_vec(doc)?;
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_slice())
+        .map_err(|_| anyhow::anyhow!("加密失败"))?;
+
+    Ok(json!({
+        "kind": BUNDLE_KIND,
+        "version": BUNDLE_VERSION,
+        "encryption": {
+            "kdf": KDF,
+            "salt": BASE64.encode(salt),
+            "cipher": CIPHER,
+            "nonce": BASE64.encode(nonce),
+        },
+        "ciphertext": BASE64.encode(ciphertext),
+    }))
+}
+
+/// 解密 [`encrypt`] 生成的封装，返回原始文档
+pub fn decrypt(envelope: &Value, passphrase: &str) -> AppResult<Value> {
+    check_version(envelope)?;
+    let enc = &envelope["encryption"];
+    if enc["kdf"] != KDF || enc["cipher"] != CIPHER {
+        anyhow::bail!("不支持的加密方式: {} / {}", enc["kdf"], enc["cipher"]);
+    }
+    let field = |value: &Value, name: &str| -> AppResult<Vec<u8>> {
+        let text = value.as_str().with_context(|| format!("加密封装缺少字段: {}", name))?;
+        BASE64
+            .decode(text)
+            .with_context(|| format!("加密封装字段无效: {}", name))
+    };
+    let salt = field(&enc["salt"], "salt")?;
+    let nonce = field(&enc["nonce"], "nonce")?;
+    let ciphertext = field(&envelope["ciphertext"], "ciphertext")?;
+    if nonce.len() != 12 {
+        anyhow::bail!("加密封装字段无效: nonce");
+    }
+
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+        .map_err(|_| anyhow::anyhow!("解密失败：口令错误或文件已损坏"))?;
+    serde_json::from_slice(&plaintext).with_context(|| "解密后的内容不是合法的 JSON")
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> AppResult<Key> {
+    let mut key = [0u8; 32];
+    argon2::Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow::anyhow!("派生密钥失败: {}", e))?;
+    Ok(key.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Bundle {
+        let provider = |app: &str, id: &str, name: &str, current: bool| BundleProvider {
+            app: app.to_string(),
+            id: id.to_string(),
+            name: name.to_string(),
+            settings_config: json!({"env": {"ANTHROPIC_AUTH_TOKEN": "sk-test"}}),
+            category: Some("custom".to_string()),
+            website_url: None,
+            notes: Some("备注".to_string()),
+            icon_color: None,
+            endpoints: None,
+            current,
+        };
+        Bundle {
+            providers: vec![
+                provider("claude", "a1", "work", true),
+                provider("claude", "a2", "personal", false),
+                provider("codex", "c1", "openai", true),
+            ],
+        }
+    }
+
+    #[test]
+    fn encrypt_round_trip() {
+        let doc = sample().to_document(BundleFormat::Bundle);
+        let envelope = encrypt(&doc, "correct horse").unwrap();
+        assert!(is_encrypted(&envelope));
+        assert!(!envelope.to_string().contains("sk-test"));
+        assert_eq!(decrypt(&envelope, "correct horse").unwrap(), doc);
+    }
+
+    #[test]
+    fn decrypt_rejects_wrong_passphrase() {
+        let envelope = encrypt(&json!({"a": 1}), "right").unwrap();
+        let err = decrypt(&envelope, "wrong").unwrap_err();
+        assert!(err.to_string().contains("口令错误"), "{err}");
+    }
+
+    #[test]
+    fn decrypt_rejects_truncated_nonce() {
+        let mut envelope = encrypt(&json!({"a": 1}), "pass").unwrap();
+        let nonce = BASE64.decode(envelope["encryption"]["nonce"].as_str().unwrap()).unwrap();
+        envelope["encryption"]["nonce"] = json!(BASE64.encode(&nonce[..8]));
+        let err = decrypt(&envelope, "pass").unwrap_err();
+        assert!(err.to_string().contains("nonce"), "{err}");
+    }
+
+    #[test]
+    fn legacy_round_trip() {
+        let bundle = sample();
+        let doc = bundle.to_document(BundleFormat::Legacy);
+        let parsed = Bundle::from_document(&doc, Path::new("config.json")).unwrap();
+
+        assert_eq!(parsed.providers.len(), bundle.providers.len());
+        for original in &bundle.providers {
+            let p = parsed.providers.iter().find(|p| p.id == original.id).unwrap();
+            assert_eq!(p.app, original.app);
+            assert_eq!(p.name, original.name);
+            assert_eq!(p.settings_config, original.settings_config);
+            assert_eq!(p.category, original.category);
+            assert_eq!(p.notes, original.notes);
+            assert_eq!(p.current, original.current);
+        }
+    }
+
+    #[test]
+    fn bundle_round_trip() {
+        let bundle = sample();
+        let doc = bundle.to_document(BundleFormat::Bundle);
+        let parsed = Bundle::from_document(&doc, Path::new("bundle.json")).unwrap();
+        assert_eq!(serde_json::to_value(&parsed.providers).unwrap(), serde_json::to_value(&bundle.providers).unwrap());
+    }
+}
@@ -1,110 +1,292 @@
-use crate::db::Provider;
+use crate::config::common::CommonConfig;
+use crate::config::paths::Paths;
+use crate::db::{AppType, Provider};
 use crate::error::AppResult;
-use crate::utils::encoding::encode_config_name;
+use crate::utils::encoding::{decode_config_name, encode_config_name};
 use anyhow::Context;
 use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::collections::HashSet;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// 缓存管理器：处理配置文件的懒加载和哈希比较
 pub struct CacheManager {
     cache_dir: PathBuf,
+    common: Option<CommonConfig>,
 }
 
 impl CacheManager {
-    pub fn new() -> AppResult<Self> {
-        let cache_dir = dirs::home_dir()
-            .ok_or_else(|| anyhow::anyhow!("无法获取用户主目录"))?
-            .join(".cc-switch/separated");
+    pub fn new(paths: &Paths) -> Self {
+        Self {
+            cache_dir: paths.cache_dir.clone(),
+            common: None,
+        }
+    }
 
-        Ok(Self { cache_dir })
+    /// 生成缓存时合并 cc-switch 的通用配置片段（仅作用于同一应用的 provider）
+    pub fn with_common(mut self, common: Option<CommonConfig>) -> Self {
+        self.common = common;
+        self
     }
 
-    /// 获取缓存文件路径
-    pub fn get_cache_path(&self, name: &str) -> PathBuf {
-        let encoded = encode_config_name(name);
-        self.cache_dir.join(format!("config-{}.json", encoded))
+    /// 获取缓存路径
+    ///
+    /// Claude 为单个 settings 文件（按 [`Provider::qualified_name`] 命名，同名配置各自独立）；
+    /// Codex 为独立的 `CODEX_HOME` 目录，Gemini 为包含 `settings.json` 的目录，二者按 provider id
+    /// 命名（`@<id>`）。`CODEX_HOME` 中还保存着 CLI 的会话与登录状态，改名时不能变化
+    pub fn get_cache_path(&self, provider: &Provider) -> PathBuf {
+        match provider.app_type {
+            AppType::Claude => self.claude_config_path(&provider.qualified_name()),
+            AppType::Codex | AppType::Gemini => self.app_dir(provider.app_type).join(home_dir_name(provider)),
+        }
     }
 
-    /// 确保缓存文件存在且是最新的，返回文件路径
-    /// 使用内容哈希比较，只在内容变化时才重新写入
-    pub fn ensure_cached(&self, provider: &Provider) -> AppResult<PathBuf> {
-        let path = self.get_cache_path(&provider.name);
-        let content = serde_json::to_vec_pretty(&provider.settings_config)
-            .with_context(|| "序列化配置失败")?;
-        let new_hash = Self::hash_content(&content);
-
-        let should_write = if path.exists() {
-            let existing = fs::read(&path).with_context(|| "读取缓存文件失败")?;
-            let existing_hash = Self::hash_content(&existing);
-            new_hash != existing_hash
-        } else {
-            true
-        };
+    fn claude_config_path(&self, name: &str) -> PathBuf {
+        self.cache_dir.join(format!("config-{}.json", encode_config_name(name)))
+    }
 
-        if should_write {
-            self.write_atomic(&path, &content)?;
+    /// 应用对应的缓存根目录
+    fn app_dir(&self, app: AppType) -> PathBuf {
+        match app {
+            AppType::Claude => self.cache_dir.clone(),
+            AppType::Codex | AppType::Gemini => self.cache_dir.join(app.as_str()),
         }
+    }
 
+    /// 获取 provider 对应的 MCP 配置文件路径（仅 Claude 使用）
+    pub fn get_mcp_path(&self, name: &str) -> PathBuf {
+        let encoded = encode_config_name(name);
+        self.cache_dir.join(format!("mcp-{}.json", encoded))
+    }
+
+    /// 写入本次运行的 MCP 配置（与 settings 相同的哈希比较策略），返回文件路径
+    pub fn ensure_mcp_config(&self, name: &str, config: &serde_json::Value) -> AppResult<PathBuf> {
+        let path = self.get_mcp_path(name);
+        let content = serde_json::to_vec_pretty(config).with_context(|| "序列化 MCP 配置失败")?;
+        self.write_if_changed(&path, &content, false)?;
         Ok(path)
     }
 
+    /// 获取 provider 专属的 Claude 配置目录（`CLAUDE_CONFIG_DIR`）
+    ///
+    /// 配置目录按 provider id 命名（`@<id>`），改名或出现同名配置时保持不变
+    pub fn get_home_path(&self, provider: &Provider) -> PathBuf {
+        self.cache_dir.join("claude-home").join(home_dir_name(provider))
+    }
+
+    /// 获取 provider 的隔离配置目录（`--isolate` / `[isolate]`，会话与历史独立保存）
+    pub fn get_isolated_home_path(&self, provider: &Provider) -> PathBuf {
+        self.cache_dir.join("claude-isolated").join(home_dir_name(provider))
+    }
+
+    /// 获取 OAuth 账号启动时使用的配置目录（`--account`）
+    pub fn get_account_home_path(&self, account: &str) -> PathBuf {
+        self.cache_dir.join("claude-accounts").join(encode_config_name(account))
+    }
+
+    /// 迁移旧版本按配置名称（`name` / `name#id`）命名的配置目录到按 id 命名的目录，
+    /// 返回被迁移的旧目录。有同名配置时无法判断 `name` 目录属于哪一个，不迁移
+    pub fn migrate_home(&self, home: &Path, provider: &Provider) -> AppResult<Option<PathBuf>> {
+        let Some(root) = home.parent() else {
+            return Ok(None);
+        };
+        if home.exists() {
+            return Ok(None);
+        }
+        let mut legacy = vec![format!("{}#{}", provider.name, provider.id)];
+        if !provider.duplicate {
+            legacy.push(provider.name.clone());
+        }
+        for name in legacy {
+            let from = root.join(encode_config_name(&name));
+            if from.is_dir() {
+                fs::rename(&from, home).with_context(|| {
+                    format!("迁移配置目录失败: {} -> {}", from.display(), home.display())
+                })?;
+                return Ok(Some(from));
+            }
+        }
+        Ok(None)
+    }
+
+    /// 端点探测结果缓存文件
+    pub fn probe_cache_path(&self) -> PathBuf {
+        self.cache_dir.join("endpoint-probes.json")
+    }
+
+    /// 技能仓库的本地检出根目录
+    pub fn skill_repos_dir(&self) -> PathBuf {
+        self.cache_dir.join("skill-repos")
+    }
+
+    /// 确保缓存文件存在且是最新的，返回文件路径
+    /// 使用内容哈希比较，只在内容变化时才重新写入
+    pub fn ensure_cached(&self, provider: &Provider) -> AppResult<PathBuf> {
+        self.materialize(provider, false)
+    }
+
     /// 强制写入缓存文件（用于 update 命令）
     pub fn force_write(&self, provider: &Provider) -> AppResult<PathBuf> {
-        let path = self.get_cache_path(&provider.name);
-        let content = serde_json::to_vec_pretty(&provider.settings_config)
-            .with_context(|| "序列化配置失败")?;
-        self.write_atomic(&path, &content)?;
+        self.materialize(provider, true)
+    }
+
+    /// 按应用类型将 provider 配置落盘
+    fn materialize(&self, provider: &Provider, force: bool) -> AppResult<PathBuf> {
+        let path = self.get_cache_path(provider);
+        if provider.app_type != AppType::Claude {
+            self.migrate_home(&path, provider)?;
+        }
+        for (file, content) in self.render(provider)? {
+            self.write_if_changed(&file, &content, force)?;
+        }
+        // 旧版本为 Gemini 写入的 .env 含明文密钥，已不再使用
+        let dotenv = path.join(".env");
+        if provider.app_type == AppType::Gemini && dotenv.is_file() {
+            fs::remove_file(&dotenv).with_context(|| format!("删除缓存文件失败: {}", dotenv.display()))?;
+        }
+        // 出现或删除同名配置后 qualified_name 在 `name` 与 `name#id` 之间切换，清理另一名称下遗留的文件
+        if provider.app_type == AppType::Claude {
+            let other = if provider.duplicate {
+                provider.name.clone()
+            } else {
+                format!("{}#{}", provider.name, provider.id)
+            };
+            self.remove_claude_files(&other)?;
+        }
         Ok(path)
     }
 
-    /// 删除指定名称的缓存文件
-    pub fn remove_cache(&self, name: &str) -> AppResult<()> {
-        let path = self.get_cache_path(name);
-        if path.exists() {
-            fs::remove_file(&path).with_context(|| format!("删除缓存文件失败: {}", path.display()))?;
+    /// 合并通用配置后实际交给 CLI 的 provider
+    pub fn effective<'p>(&self, provider: &'p Provider) -> AppResult<Cow<'p, Provider>> {
+        match &self.common {
+            Some(common) if common.app() == provider.app_type => Ok(Cow::Owned(common.apply(provider)?)),
+            _ => Ok(Cow::Borrowed(provider)),
+        }
+    }
+
+    /// 生成 provider 对应的缓存文件（路径与内容），不写入磁盘
+    pub fn render(&self, provider: &Provider) -> AppResult<Vec<(PathBuf, Vec<u8>)>> {
+        provider.check()?;
+        let provider = self.effective(provider)?;
+        let path = self.get_cache_path(&provider);
+        let mut files = Vec::new();
+        match provider.app_type {
+            AppType::Claude => {
+                let content = serde_json::to_vec_pretty(&provider.settings_config)
+                    .with_context(|| "序列化配置失败")?;
+                files.push((path, content));
+            }
+            AppType::Codex => {
+                // cc-switch 中 Codex 的 settings_config 形如 {"auth": {...}, "config": "<config.toml>"}
+                let settings = &provider.settings_config;
+                if let Some(auth) = settings.get("auth").filter(|v| !v.is_null()) {
+                    let content =
+                        serde_json::to_vec_pretty(auth).with_context(|| "序列化 auth.json 失败")?;
+                    files.push((path.join("auth.json"), content));
+                }
+                let config = settings.get("config").and_then(|v| v.as_str()).unwrap_or("");
+                files.push((path.join("config.toml"), config.as_bytes().to_vec()));
+            }
+            AppType::Gemini => {
+                // cc-switch 中 Gemini 的 settings_config 形如 {"env": {...}, "config": {...}}
+                // env 在启动时直接注入，不落盘明文密钥
+                let settings = &provider.settings_config;
+                let config = settings
+                    .get("config")
+                    .filter(|v| v.is_object())
+                    .cloned()
+                    .unwrap_or_else(|| serde_json::json!({}));
+                let content = serde_json::to_vec_pretty(&config)
+                    .with_context(|| "序列化 settings.json 失败")?;
+                files.push((path.join("settings.json"), content));
+            }
+        }
+        Ok(files)
+    }
+
+    /// 仅在内容哈希变化（或强制）时写入
+    fn write_if_changed(&self, path: &Path, content: &[u8], force: bool) -> AppResult<()> {
+        let should_write = if !force && path.exists() {
+            let existing = fs::read(path).with_context(|| "读取缓存文件失败")?;
+            Self::hash_content(content) != Self::hash_content(&existing)
+        } else {
+            true
+        };
+
+        if should_write {
+            self.write_atomic(path, content)?;
         }
         Ok(())
     }
 
-    /// 列出所有缓存的配置名称
-    pub fn list_cached_names(&self) -> AppResult<Vec<String>> {
-        let mut results = Vec::new();
-        if !self.cache_dir.exists() {
-            return Ok(results);
+    /// 删除 provider 的缓存：Claude 删除 settings 与 MCP 文件；Codex/Gemini 只删除 ccstart
+    /// 生成的文件，目录中 CLI 自己的会话、历史与登录状态保留（目录为空时一并删除）
+    pub fn remove_cache(&self, provider: &Provider) -> AppResult<()> {
+        match provider.app_type {
+            AppType::Claude => self.remove_claude_files(&provider.qualified_name()),
+            AppType::Codex | AppType::Gemini => remove_generated(&self.get_cache_path(provider)),
         }
+    }
 
-        for entry in fs::read_dir(&self.cache_dir)? {
-            let entry = entry?;
-            let path = entry.path();
-            if !path.is_file() {
-                continue;
-            }
-            if let Some(fname) = path.file_name().and_then(|s| s.to_str())
-                && let Some(rest) = fname.strip_prefix("config-")
-                && let Some(encoded) = rest.strip_suffix(".json")
-                && let Ok(decoded) = crate::utils::encoding::decode_config_name(encoded)
-            {
-                results.push(decoded);
+    fn remove_claude_files(&self, name: &str) -> AppResult<()> {
+        for path in [self.claude_config_path(name), self.get_mcp_path(name)] {
+            if path.is_file() {
+                fs::remove_file(&path).with_context(|| format!("删除缓存文件失败: {}", path.display()))?;
             }
         }
-        results.sort();
-        Ok(results)
+        Ok(())
     }
 
-    /// 清理不在数据库中的缓存文件
-    pub fn cleanup_stale(&self, valid_names: &[String]) -> AppResult<Vec<String>> {
-        let cached = self.list_cached_names()?;
-        let valid_set: std::collections::HashSet<_> = valid_names.iter().collect();
+    /// 清理数据库中已不存在的 provider 的缓存，返回被清理的条目名称
+    ///
+    /// Codex/Gemini 目录只删除 ccstart 生成的文件（见 [`remove_cache`](Self::remove_cache)）
+    pub fn cleanup_stale(&self, app: AppType, providers: &[Provider]) -> AppResult<Vec<String>> {
+        let dir = self.app_dir(app);
         let mut removed = Vec::new();
+        if !dir.exists() {
+            return Ok(removed);
+        }
 
-        for name in cached {
-            if !valid_set.contains(&name) {
-                self.remove_cache(&name)?;
-                removed.push(name);
-            }
+        let valid: HashSet<String> = match app {
+            AppType::Claude => providers.iter().map(|p| p.qualified_name().into_owned()).collect(),
+            AppType::Codex | AppType::Gemini => providers.iter().map(home_dir_name).collect(),
+        };
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(&dir).with_context(|| format!("读取缓存目录失败: {}", dir.display()))? {
+            entries.push(entry?.path());
         }
+        entries.sort();
 
+        for path in entries {
+            let Some(fname) = path.file_name().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            match app {
+                AppType::Claude => {
+                    if !path.is_file() {
+                        continue;
+                    }
+                    let Some(encoded) = fname.strip_prefix("config-").and_then(|r| r.strip_suffix(".json")) else {
+                        continue;
+                    };
+                    let Ok(name) = decode_config_name(encoded) else {
+                        continue;
+                    };
+                    if !valid.contains(&name) {
+                        self.remove_claude_files(&name)?;
+                        removed.push(name);
+                    }
+                }
+                AppType::Codex | AppType::Gemini => {
+                    if !path.is_dir() || valid.contains(fname) || !has_generated(&path) {
+                        continue;
+                    }
+                    remove_generated(&path)?;
+                    removed.push(decode_config_name(fname).unwrap_or_else(|_| fname.to_string()));
+                }
+            }
+        }
         Ok(removed)
     }
 
@@ -116,15 +298,144 @@ impl CacheManager {
     }
 
     /// 原子写入：先写临时文件再重命名
-    fn write_atomic(&self, target: &PathBuf, data: &[u8]) -> AppResult<()> {
-        fs::create_dir_all(&self.cache_dir)
-            .with_context(|| format!("创建缓存目录失败: {}", self.cache_dir.display()))?;
+    fn write_atomic(&self, target: &Path, data: &[u8]) -> AppResult<()> {
+        write_atomic(target, data)
+    }
+}
 
-        let tmp = target.with_extension("json.tmp");
-        fs::write(&tmp, data).with_context(|| format!("写入临时文件失败: {}", tmp.display()))?;
-        fs::rename(&tmp, target)
-            .with_context(|| format!("重命名文件失败: {} -> {}", tmp.display(), target.display()))?;
+/// 原子写入：先写临时文件（文件名含进程号，并发写入互不干扰）再重命名
+pub fn write_atomic(target: &Path, data: &[u8]) -> AppResult<()> {
+    if let Some(dir) = target.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("创建缓存目录失败: {}", dir.display()))?;
+    }
 
-        Ok(())
+    let mut tmp_name = target.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(format!(".{}.tmp", std::process::id()));
+    let tmp = target.with_file_name(tmp_name);
+    fs::write(&tmp, data).with_context(|| format!("写入临时文件失败: {}", tmp.display()))?;
+    fs::rename(&tmp, target)
+        .with_context(|| format!("重命名文件失败: {} -> {}", tmp.display(), target.display()))?;
+
+    Ok(())
+}
+
+/// ccstart 在 Codex/Gemini 目录中生成的文件（`.env` 为旧版本生成）
+const GENERATED_FILES: &[&str] = &["auth.json", "config.toml", "settings.json", ".env"];
+
+fn has_generated(dir: &Path) -> bool {
+    GENERATED_FILES.iter().any(|f| dir.join(f).is_file())
+}
+
+/// 删除目录中 ccstart 生成的文件；目录因此变空时删除目录
+fn remove_generated(dir: &Path) -> AppResult<()> {
+    for file in GENERATED_FILES {
+        let path = dir.join(file);
+        if path.is_file() {
+            fs::remove_file(&path).with_context(|| format!("删除缓存文件失败: {}", path.display()))?;
+        }
+    }
+    if dir.read_dir().is_ok_and(|mut entries| entries.next().is_none()) {
+        fs::remove_dir(dir).with_context(|| format!("删除缓存目录失败: {}", dir.display()))?;
+    }
+    Ok(())
+}
+
+/// 配置目录名：`@<id>`，与按名称命名的旧目录区分
+fn home_dir_name(provider: &Provider) -> String {
+    encode_config_name(&format!("@{}", provider.id))
+}
+
+/// 提取 settings 中 `env` 对象的键值对（非字符串值按 JSON 文本处理）
+pub fn env_pairs(settings: &serde_json::Value) -> Vec<(String, String)> {
+    settings
+        .get("env")
+        .and_then(|v| v.as_object())
+        .map(|map| {
+            map.iter()
+                .map(|(key, value)| {
+                    let value = match value {
+                        serde_json::Value::String(s) => s.clone(),
+                        other => other.to_string(),
+                    };
+                    (key.clone(), value)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codex(id: &str, name: &str) -> Provider {
+        Provider {
+            id: id.to_string(),
+            app_type: AppType::Codex,
+            name: name.to_string(),
+            settings_config: serde_json::json!({ "auth": { "OPENAI_API_KEY": "sk-test" }, "config": "model = \"o3\"\n" }),
+            load_error: None,
+            duplicate: false,
+            category: None,
+            website_url: None,
+            notes: None,
+            icon_color: None,
+            is_current: false,
+        }
+    }
+
+    fn manager(test: &str) -> CacheManager {
+        let cache_dir = std::env::temp_dir().join(format!("ccstart-cache-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&cache_dir);
+        CacheManager { cache_dir, common: None }
+    }
+
+    #[test]
+    fn codex_sessions_survive_rename_and_update() {
+        let cache = manager("rename");
+        let old = codex("c1", "work");
+        let home = cache.ensure_cached(&old).unwrap();
+        let session = home.join("sessions/2026/rollout.jsonl");
+        fs::create_dir_all(session.parent().unwrap()).unwrap();
+        fs::write(&session, "{}").unwrap();
+
+        // 改名：目录按 id 命名，不变
+        let renamed = codex("c1", "work-renamed");
+        assert_eq!(cache.force_write(&renamed).unwrap(), home);
+        // update：仍存在的 provider 不清理
+        assert!(cache.cleanup_stale(AppType::Codex, &[renamed]).unwrap().is_empty());
+        assert!(session.is_file());
+        assert!(home.join("config.toml").is_file());
+
+        // provider 被删除后只清理 ccstart 生成的文件
+        let removed = cache.cleanup_stale(AppType::Codex, &[]).unwrap();
+        assert_eq!(removed, vec!["@c1".to_string()]);
+        assert!(session.is_file());
+        assert!(!home.join("config.toml").exists());
+        assert!(!home.join("auth.json").exists());
+        let _ = fs::remove_dir_all(&cache.cache_dir);
+    }
+
+    #[test]
+    fn legacy_name_keyed_home_is_migrated() {
+        let cache = manager("legacy");
+        let legacy = cache.app_dir(AppType::Codex).join("work");
+        fs::create_dir_all(legacy.join("sessions")).unwrap();
+        fs::write(legacy.join("sessions/a.jsonl"), "{}").unwrap();
+
+        let home = cache.ensure_cached(&codex("c1", "work")).unwrap();
+        assert!(home.join("sessions/a.jsonl").is_file());
+        assert!(!legacy.exists());
+        let _ = fs::remove_dir_all(&cache.cache_dir);
+    }
+
+    #[test]
+    fn remove_cache_deletes_empty_generated_home() {
+        let cache = manager("remove");
+        let provider = codex("c2", "tmp");
+        let home = cache.ensure_cached(&provider).unwrap();
+        cache.remove_cache(&provider).unwrap();
+        assert!(!home.exists());
+        let _ = fs::remove_dir_all(&cache.cache_dir);
     }
 }
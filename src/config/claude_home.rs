@@ -0,0 +1,224 @@
+use crate::config::accounts::{
+    main_state_path, write_oauth_account, write_private_atomic, CREDENTIALS_FILE, STATE_FILE,
+};
+use crate::config::skills::ResolvedSkill;
+use crate::error::AppResult;
+use anyhow::Context;
+use serde::Deserialize;
+use serde_json::Value;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 主 Claude 配置目录：优先 `CLAUDE_CONFIG_DIR`，否则为 `~/.claude`
+pub fn main_config_dir() -> AppResult<PathBuf> {
+    if let Some(dir) = std::env::var_os("CLAUDE_CONFIG_DIR").filter(|v| !v.is_empty()) {
+        return Ok(PathBuf::from(dir));
+    }
+    Ok(dirs::home_dir()
+        .ok_or_else(|| anyhow::anyhow!("无法获取用户主目录"))?
+        .join(".claude"))
+}
+
+/// 主配置文件：未设置 `CLAUDE_CONFIG_DIR` 时 claude 使用 `~/.claude.json`
+pub fn main_config_file() -> Option<PathBuf> {
+    if std::env::var_os("CLAUDE_CONFIG_DIR").is_some_and(|v| !v.is_empty()) {
+        return None;
+    }
+    dirs::home_dir().map(|h| h.join(".claude.json"))
+}
+
+/// 隔离目录默认从主配置目录共享的条目
+pub const DEFAULT_SHARED: &[&str] = &["agents", "commands", "CLAUDE.md"];
+
+/// 隔离目录共享条目的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShareMode {
+    /// 符号链接到主配置目录（修改对所有 provider 可见）
+    #[default]
+    Link,
+    /// 首次启动时复制一份，此后各自独立
+    Copy,
+}
+
+/// 单个 provider 的 Claude 配置目录（启动时作为 `CLAUDE_CONFIG_DIR`）
+pub struct ClaudeHome {
+    root: PathBuf,
+}
+
+impl ClaudeHome {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.root
+    }
+
+    /// 构建叠加目录：主配置目录中的条目以符号链接共享（会话、凭据保持一致），
+    /// `skills/` 则由主目录中的技能与解析出的已安装技能组合而成。
+    /// 返回链接到 `skills/` 下的技能名称
+    pub fn prepare_overlay(&self, main: &Path, skills: &[ResolvedSkill]) -> AppResult<Vec<String>> {
+        fs::create_dir_all(&self.root)
+            .with_context(|| format!("创建配置目录失败: {}", self.root.display()))?;
+
+        if main.is_dir() {
+            for entry in fs::read_dir(main)
+                .with_context(|| format!("读取目录失败: {}", main.display()))?
+            {
+                let entry = entry?;
+                if entry.file_name() == "skills" {
+                    continue;
+                }
+                self.link_entry(&entry.path(), &self.root.join(entry.file_name()))?;
+            }
+        }
+        if let Some(file) = main_config_file().filter(|f| f.exists()) {
+            self.link_entry(&file, &self.root.join(".claude.json"))?;
+        }
+
+        self.rebuild_skills(&main.join("skills"), skills)
+    }
+
+    /// 构建账号目录：除凭据与状态文件外的条目以符号链接共享，凭据使用账号自己的副本（0600）。
+    /// 状态文件（`.claude.json`）首次从主配置复制，此后各账号独立，其中的 `oauthAccount`
+    /// 用于识别会话中是否登录了其它账号
+    pub fn prepare_account(&self, main: &Path, credentials: &[u8], oauth: Option<&Value>) -> AppResult<PathBuf> {
+        fs::create_dir_all(&self.root)
+            .with_context(|| format!("创建配置目录失败: {}", self.root.display()))?;
+
+        if main.is_dir() {
+            for entry in fs::read_dir(main)
+                .with_context(|| format!("读取目录失败: {}", main.display()))?
+            {
+                let entry = entry?;
+                if entry.file_name() == CREDENTIALS_FILE || entry.file_name() == STATE_FILE {
+                    continue;
+                }
+                self.link_entry(&entry.path(), &self.root.join(entry.file_name()))?;
+            }
+        }
+
+        let state = self.root.join(STATE_FILE);
+        let linked = fs::symlink_metadata(&state).is_ok_and(|m| m.file_type().is_symlink());
+        if linked || !state.exists() {
+            // 旧版本链接到主配置文件；改为独立副本
+            let content = fs::read(main_state_path()?).unwrap_or_else(|_| b"{}".to_vec());
+            if linked {
+                fs::remove_file(&state).with_context(|| format!("删除失败: {}", state.display()))?;
+            }
+            write_private_atomic(&state, &content)?;
+        }
+        if let Some(oauth) = oauth {
+            write_oauth_account(&state, oauth)?;
+        }
+
+        let path = self.root.join(CREDENTIALS_FILE);
+        write_private_atomic(&path, credentials)?;
+        Ok(path)
+    }
+
+    /// 构建隔离目录：只共享 `shared` 中列出的条目，会话、历史与凭据等其余内容各 provider 独立。
+    /// 返回实际共享的条目
+    pub fn prepare_isolated(&self, main: &Path, shared: &[String], mode: ShareMode) -> AppResult<Vec<String>> {
+        fs::create_dir_all(&self.root)
+            .with_context(|| format!("创建配置目录失败: {}", self.root.display()))?;
+
+        let mut result = Vec::new();
+        for name in shared {
+            if name.is_empty() || name.contains(['/', '\\']) || name == "." || name == ".." {
+                anyhow::bail!("无效的共享条目: '{}'（只能是主配置目录下的文件或目录名）", name);
+            }
+            let src = main.join(name);
+            if !src.exists() {
+                continue;
+            }
+            let dst = self.root.join(name);
+            match mode {
+                ShareMode::Link => self.link_entry(&src, &dst)?,
+                ShareMode::Copy => {
+                    if fs::symlink_metadata(&dst).is_err() {
+                        copy_recursive(&src, &dst)
+                            .with_context(|| format!("复制失败: {} -> {}", src.display(), dst.display()))?;
+                    }
+                }
+            }
+            result.push(name.clone());
+        }
+        Ok(result)
+    }
+
+    /// 重建 `skills/` 目录（其中只包含符号链接）
+    pub fn rebuild_skills(&self, main_skills: &Path, skills: &[ResolvedSkill]) -> AppResult<Vec<String>> {
+        let dir = self.root.join("skills");
+        match fs::symlink_metadata(&dir) {
+            Ok(meta) if meta.is_dir() => fs::remove_dir_all(&dir)?,
+            Ok(_) => fs::remove_file(&dir)?,
+            Err(_) => {}
+        }
+        fs::create_dir_all(&dir).with_context(|| format!("创建目录失败: {}", dir.display()))?;
+
+        let mut linked = Vec::new();
+        if main_skills.is_dir() {
+            for entry in fs::read_dir(main_skills)? {
+                let entry = entry?;
+                symlink(&entry.path(), &dir.join(entry.file_name()))?;
+                linked.push(entry.file_name().to_string_lossy().to_string());
+            }
+        }
+        for skill in skills {
+            let Some(path) = &skill.path else { continue };
+            if linked.contains(&skill.dir_name) {
+                continue;
+            }
+            symlink(path, &dir.join(&skill.dir_name))
+                .with_context(|| format!("链接技能失败: {}", skill.key))?;
+            linked.push(skill.dir_name.clone());
+        }
+        linked.sort();
+        Ok(linked)
+    }
+
+    /// 确保 `dst` 为指向 `src` 的符号链接；若 `dst` 已是真实文件（例如被 claude 改写），保持不动
+    fn link_entry(&self, src: &Path, dst: &Path) -> AppResult<()> {
+        match fs::symlink_metadata(dst) {
+            Ok(meta) if meta.file_type().is_symlink() => {
+                if fs::read_link(dst).ok().as_deref() == Some(src) {
+                    return Ok(());
+                }
+                fs::remove_file(dst)?;
+            }
+            Ok(_) => return Ok(()),
+            Err(_) => {}
+        }
+        symlink(src, dst).with_context(|| format!("创建符号链接失败: {}", dst.display()))
+    }
+}
+
+fn copy_recursive(src: &Path, dst: &Path) -> io::Result<()> {
+    if src.is_dir() {
+        fs::create_dir_all(dst)?;
+        for entry in fs::read_dir(src)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &dst.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        fs::copy(src, dst).map(|_| ())
+    }
+}
+
+#[cfg(unix)]
+fn symlink(src: &Path, dst: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(src, dst)
+}
+
+#[cfg(windows)]
+fn symlink(src: &Path, dst: &Path) -> io::Result<()> {
+    if src.is_dir() {
+        std::os::windows::fs::symlink_dir(src, dst)
+    } else {
+        std::os::windows::fs::symlink_file(src, dst)
+    }
+}
@@ -0,0 +1,173 @@
+use crate::db::{AppType, Database, Provider};
+use crate::error::AppResult;
+use anyhow::Context;
+use serde_json::{Map, Value};
+
+/// cc-switch GUI 的通用配置片段（settings 表中的 `common_config_<app>`）
+///
+/// 与 GUI 一致，片段合并在 provider 自身的 settings 之下：同名字段以 provider 为准
+#[derive(Debug, Clone)]
+pub struct CommonConfig {
+    app: AppType,
+    snippet: Snippet,
+}
+
+#[derive(Debug, Clone)]
+enum Snippet {
+    /// Claude / Gemini：合并到 settings_config
+    Json(Map<String, Value>),
+    /// Codex：合并到 `config` 中的 config.toml
+    Toml(toml::Table),
+}
+
+impl CommonConfig {
+    /// 读取指定应用的通用配置；未设置、为空或无法解析时返回 None（解析失败给出警告）
+    pub fn load(db: Option<&Database>, app: AppType) -> AppResult<Option<Self>> {
+        let Some(db) = db else {
+            return Ok(None);
+        };
+        let Some(raw) = db.settings().common_config(app)? else {
+            return Ok(None);
+        };
+        match Self::parse(app, &raw) {
+            Ok(config) => Ok(config),
+            Err(e) => {
+                eprintln!("[WARN] 已忽略 cc-switch 的 {} 通用配置: {:#}", app.label(), e);
+                Ok(None)
+            }
+        }
+    }
+
+    fn parse(app: AppType, raw: &str) -> AppResult<Option<Self>> {
+        if raw.trim().is_empty() {
+            return Ok(None);
+        }
+        let snippet = match app {
+            AppType::Codex => {
+                let table: toml::Table = toml::from_str(raw).with_context(|| "不是合法的 TOML")?;
+                if table.is_empty() {
+                    return Ok(None);
+                }
+                Snippet::Toml(table)
+            }
+            AppType::Claude | AppType::Gemini => {
+                let mut value: Value = serde_json::from_str(raw).with_context(|| "不是合法的 JSON")?;
+                // 兼容以 JSON 字符串形式再次编码的值
+                if let Value::String(inner) = &value {
+                    value = serde_json::from_str(inner).with_context(|| "不是合法的 JSON")?;
+                }
+                match value {
+                    Value::Object(map) if map.is_empty() => return Ok(None),
+                    Value::Object(map) => Snippet::Json(map),
+                    _ => anyhow::bail!("应为 JSON 对象"),
+                }
+            }
+        };
+        Ok(Some(Self { app, snippet }))
+    }
+
+    pub fn app(&self) -> AppType {
+        self.app
+    }
+
+    /// 返回合并通用配置后的 provider
+    pub fn apply(&self, provider: &Provider) -> AppResult<Provider> {
+        let mut merged = provider.clone();
+        match &self.snippet {
+            Snippet::Json(snippet) => {
+                let mut base = Value::Object(snippet.clone());
+                merge_json(&mut base, &provider.settings_config);
+                merged.settings_config = base;
+            }
+            Snippet::Toml(snippet) => {
+                let own = provider_toml(provider)?;
+                let mut base = snippet.clone();
+                merge_toml(&mut base, &own);
+                let text = toml::to_string(&base).with_context(|| "序列化 config.toml 失败")?;
+                merged.settings_config["config"] = Value::String(text);
+            }
+        }
+        Ok(merged)
+    }
+
+    /// 合并后来自通用配置（provider 自身未设置）的字段路径
+    pub fn inherited_keys(&self, provider: &Provider) -> AppResult<Vec<String>> {
+        let mut keys = Vec::new();
+        match &self.snippet {
+            Snippet::Json(snippet) => {
+                let own = provider.settings_config.as_object().cloned().unwrap_or_default();
+                json_keys(snippet, &own, "", &mut keys);
+            }
+            Snippet::Toml(snippet) => {
+                toml_keys(snippet, &provider_toml(provider)?, "config.toml:", &mut keys);
+            }
+        }
+        Ok(keys)
+    }
+}
+
+/// provider 自身的 config.toml
+fn provider_toml(provider: &Provider) -> AppResult<toml::Table> {
+    let text = provider.settings_config.get("config").and_then(Value::as_str).unwrap_or("");
+    toml::from_str(text).with_context(|| format!("配置 '{}' 的 config.toml 无法解析", provider.name))
+}
+
+/// 将 `over` 深度合并到 `base`：对象逐键合并，其它值以 `over` 为准
+pub fn merge_json(base: &mut Value, over: &Value) {
+    match (base, over) {
+        (Value::Object(base), Value::Object(over)) => {
+            for (key, value) in over {
+                match base.get_mut(key) {
+                    Some(existing) => merge_json(existing, value),
+                    None => {
+                        base.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (base, over) => *base = over.clone(),
+    }
+}
+
+fn merge_toml(base: &mut toml::Table, over: &toml::Table) {
+    for (key, value) in over {
+        match (base.get_mut(key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(table)) => merge_toml(existing, table),
+            _ => {
+                base.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+fn join(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() || prefix.ends_with(':') {
+        format!("{}{}", prefix, key)
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+fn json_keys(snippet: &Map<String, Value>, own: &Map<String, Value>, prefix: &str, out: &mut Vec<String>) {
+    for (key, value) in snippet {
+        match (value, own.get(key)) {
+            (_, None) => out.push(join(prefix, key)),
+            (Value::Object(inner), Some(Value::Object(own_inner))) => {
+                json_keys(inner, own_inner, &join(prefix, key), out)
+            }
+            _ => {}
+        }
+    }
+}
+
+fn toml_keys(snippet: &toml::Table, own: &toml::Table, prefix: &str, out: &mut Vec<String>) {
+    for (key, value) in snippet {
+        match (value, own.get(key)) {
+            (_, None) => out.push(join(prefix, key)),
+            (toml::Value::Table(inner), Some(toml::Value::Table(own_inner))) => {
+                toml_keys(inner, own_inner, &join(prefix, key), out)
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use crate::config::cache::write_atomic;
+use crate::db::{AppType, Provider};
+use crate::error::AppResult;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use toml_edit::DocumentMut;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// 探测结果默认缓存时间（秒）
+pub const DEFAULT_PROBE_TTL_SECS: u64 = 600;
+
+/// 单个端点的探测超时
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 各应用在 env 中保存 base URL 的键
+fn env_key(app: AppType) -> Option<&'static str> {
+    match app {
+        AppType::Claude => Some("ANTHROPIC_BASE_URL"),
+        AppType::Gemini => Some("GOOGLE_GEMINI_BASE_URL"),
+        AppType::Codex => None,
+    }
+}
+
+/// 读取 provider 当前配置的 base URL
+pub fn base_url(provider: &Provider) -> Option<String> {
+    match env_key(provider.app_type) {
+        Some(key) => provider.settings_config["env"][key].as_str().map(str::to_string),
+        None => {
+            let doc = codex_config(provider).parse::<DocumentMut>().ok()?;
+            let name = doc.get("model_provider")?.as_str()?;
+            doc.get("model_providers")?
+                .get(name)?
+                .get("base_url")?
+                .as_str()
+                .map(str::to_string)
+        }
+    }
+}
+
+/// 将 base URL 写入 provider 的配置（仅修改内存中的副本，随后由 CacheManager 落盘）。
+/// Codex 修改 `model_provider` 指向的 `[model_providers.<name>]` 中已有的 base_url
+pub fn set_base_url(provider: &mut Provider, url: &str) -> AppResult<()> {
+    match env_key(provider.app_type) {
+        Some(key) => {
+            if !provider.settings_config["env"].is_object() {
+                provider.settings_config["env"] = serde_json::json!({});
+            }
+            provider.settings_config["env"][key] = Value::String(url.to_string());
+        }
+        None => {
+            let mut doc = codex_config(provider)
+                .parse::<DocumentMut>()
+                .with_context(|| format!("Codex 配置 '{}' 的 config 不是合法的 TOML", provider.name))?;
+            let name = doc
+                .get("model_provider")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow::anyhow!("Codex 配置 '{}' 没有 model_provider，无法切换端点", provider.name))?
+                .to_string();
+            let value = doc
+                .get_mut("model_providers")
+                .and_then(|t| t.get_mut(&name))
+                .and_then(|t| t.as_table_like_mut())
+                .and_then(|t| t.get_mut("base_url"))
+                .and_then(|v| v.as_value_mut())
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Codex 配置 '{}' 的 [model_providers.{}] 中没有 base_url，无法切换端点",
+                        provider.name,
+                        name
+                    )
+                })?;
+            let decor = value.decor().clone();
+            *value = url.into();
+            *value.decor_mut() = decor;
+            provider.settings_config["config"] = Value::String(doc.to_string());
+        }
+    }
+    Ok(())
+}
+
+fn codex_config(provider: &Provider) -> &str {
+    provider.settings_config["config"].as_str().unwrap_or("")
+}
+
+/// 候选端点：provider_endpoints 中记录的 URL；当前配置的 base URL 若不在其中则排在最前
+pub fn candidates(provider: &Provider, recorded: Vec<String>) -> Vec<String> {
+    let mut urls = Vec::new();
+    if let Some(current) = base_url(provider)
+        && !recorded.iter().any(|u| same_url(u, &current))
+    {
+        urls.push(current);
+    }
+    for url in recorded {
+        if !urls.iter().any(|u| same_url(u, &url)) {
+            urls.push(url);
+        }
+    }
+    urls
+}
+
+/// URL 中的主机部分（含端口），用于简要展示
+pub fn host(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    rest.split(['/', '?', '#']).next().unwrap_or(rest)
+}
+
+/// 忽略末尾 `/` 比较两个 URL
+pub fn same_url(a: &str, b: &str) -> bool {
+    a.trim_end_matches('/') == b.trim_end_matches('/')
+}
+
+/// 单条探测记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ProbeRecord {
+    /// 延迟（毫秒）；不可达时为 None
+    latency_ms: Option<u64>,
+    /// 探测时间（Unix 秒）
+    probed_at: u64,
+}
+
+/// 探测结果缓存（JSON 文件），在 TTL 内复用上次结果
+pub struct ProbeCache {
+    path: PathBuf,
+    ttl: u64,
+    entries: HashMap<String, ProbeRecord>,
+}
+
+impl ProbeCache {
+    pub fn load(path: PathBuf, ttl: u64) -> Self {
+        let entries = fs::read(&path)
+            .ok()
+            .and_then(|data| serde_json::from_slice(&data).ok())
+            .unwrap_or_default();
+        Self { path, ttl, entries }
+    }
+
+    /// 未过期的缓存结果：外层 None 表示需要重新探测
+    pub fn get(&self, url: &str) -> Option<Option<u64>> {
+        let record = self.entries.get(url)?;
+        (now_secs().saturating_sub(record.probed_at) < self.ttl).then_some(record.latency_ms)
+    }
+
+    /// 并发探测所有候选端点（命中缓存的跳过），返回与输入顺序一致的延迟
+    pub fn probe_all(&mut self, urls: &[String]) -> Vec<Option<u64>> {
+        self.probe_with(urls, probe)
+    }
+
+    /// 本次探测的结果直接返回，TTL 只决定是否需要重新探测（ttl = 0 时每次都探测）
+    fn probe_with(&mut self, urls: &[String], probe: fn(&str) -> Option<u64>) -> Vec<Option<u64>> {
+        let mut results: Vec<Option<Option<u64>>> = urls.iter().map(|u| self.get(u)).collect();
+        let handles: Vec<_> = urls
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| results[*i].is_none())
+            .map(|(i, url)| {
+                let url = url.clone();
+                thread::spawn(move || (i, probe(&url), url))
+            })
+            .collect();
+        for handle in handles {
+            if let Ok((i, latency_ms, url)) = handle.join() {
+                results[i] = Some(latency_ms);
+                let record = ProbeRecord {
+                    latency_ms,
+                    probed_at: now_secs(),
+                };
+                self.entries.insert(url, record);
+            }
+        }
+
+        results.into_iter().map(Option::flatten).collect()
+    }
+
+    /// 保存缓存文件
+    pub fn save(&self) -> AppResult<()> {
+        let content = serde_json::to_vec_pretty(&self.entries).with_context(|| "序列化探测结果失败")?;
+        // 多个 ccstart 同时探测时，读者不会看到写了一半的文件
+        write_atomic(&self.path, &content)
+    }
+}
+
+/// 对单个端点发起 HEAD 请求（包含 TCP + TLS 握手），返回耗时；
+/// 任何 HTTP 响应（包括 4xx/5xx）都视为可达
+fn probe(url: &str) -> Option<u64> {
+    let agent = ureq::AgentBuilder::new().timeout(PROBE_TIMEOUT).build();
+    let start = Instant::now();
+    match agent.head(url).call() {
+        Ok(_) | Err(ureq::Error::Status(_, _)) => Some(start.elapsed().as_millis() as u64),
+        Err(ureq::Error::Transport(_)) => None,
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codex(config: &str) -> Provider {
+        Provider {
+            id: "c1".to_string(),
+            app_type: AppType::Codex,
+            name: "codex".to_string(),
+            settings_config: serde_json::json!({ "config": config }),
+            load_error: None,
+            duplicate: false,
+            category: None,
+            website_url: None,
+            notes: None,
+            icon_color: None,
+            is_current: false,
+        }
+    }
+
+    const CONFIG: &str = r#"model_provider = "b"
+
+[model_providers.a]
+base_url = "https://a.example/v1"
+
+[model_providers.b]
+name = "B"
+base_url = "https://b.example/v1" # 主线路
+"#;
+
+    #[test]
+    fn codex_base_url_follows_model_provider() {
+        assert_eq!(base_url(&codex(CONFIG)).as_deref(), Some("https://b.example/v1"));
+    }
+
+    #[test]
+    fn codex_set_base_url_edits_only_the_selected_table() {
+        let mut provider = codex(CONFIG);
+        set_base_url(&mut provider, "https://b2.example/v1").unwrap();
+        let config = codex_config(&provider);
+        assert!(config.contains("base_url = \"https://a.example/v1\""));
+        assert!(config.contains("base_url = \"https://b2.example/v1\" # 主线路"));
+        assert_eq!(base_url(&provider).as_deref(), Some("https://b2.example/v1"));
+    }
+
+    #[test]
+    fn codex_set_base_url_requires_existing_entry() {
+        let mut provider = codex("model_provider = \"a\"\n[model_providers.a]\nname = \"A\"\n");
+        assert!(set_base_url(&mut provider, "https://x.example").is_err());
+        let mut provider = codex("[model_providers.a]\nbase_url = \"https://a.example\"\n");
+        assert!(set_base_url(&mut provider, "https://x.example").is_err());
+    }
+
+    #[test]
+    fn same_url_ignores_trailing_slash() {
+        assert!(same_url("https://a.example/v1/", "https://a.example/v1"));
+        assert!(!same_url("https://a.example/v1", "https://a.example/v2"));
+    }
+
+    #[test]
+    fn probe_results_are_returned_even_with_zero_ttl() {
+        let urls = vec!["https://up.example".to_string(), "https://down.example".to_string()];
+        let fake: fn(&str) -> Option<u64> = |url| url.contains("up").then_some(7);
+        let path = std::env::temp_dir().join(format!("ccstart-probe-{}.json", std::process::id()));
+
+        let mut cache = ProbeCache::load(path.clone(), 0);
+        assert_eq!(cache.probe_with(&urls, fake), vec![Some(7), None]);
+
+        // TTL 内直接使用缓存，不再探测
+        let mut cache = ProbeCache::load(path, 60);
+        cache.probe_with(&urls, fake);
+        assert_eq!(cache.probe_with(&urls, |_| None), vec![Some(7), None]);
+    }
+}
@@ -0,0 +1,185 @@
+use crate::config::claude_home::{ShareMode, DEFAULT_SHARED};
+use crate::config::rules::Rule;
+use crate::db::Provider;
+use crate::error::AppResult;
+use anyhow::Context;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// ccstart 自身的配置文件（`~/.config/ccstart/config.toml`）
+///
+/// 各节在使用时才解析：某一节写错只影响用到它的功能，启动等路径警告后使用默认值
+#[derive(Debug, Default)]
+pub struct ConfigFile {
+    path: PathBuf,
+    table: toml::Table,
+}
+
+/// `[isolate]`：为指定 provider 使用独立的 `CLAUDE_CONFIG_DIR`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct IsolateConfig {
+    /// 启用隔离的配置名称（`*` 表示全部）
+    #[serde(default)]
+    pub providers: Vec<String>,
+    /// 从主配置目录共享的条目（默认 agents、commands、CLAUDE.md）
+    #[serde(default)]
+    pub share: Option<Vec<String>>,
+    /// 共享方式：`link`（默认）或 `copy`
+    #[serde(default)]
+    pub mode: ShareMode,
+}
+
+impl IsolateConfig {
+    /// 按名称、`name#id` 或 `@id` 判断 provider 是否启用隔离
+    pub fn enabled_for(&self, provider: &Provider) -> bool {
+        self.providers.iter().any(|p| {
+            p == "*"
+                || *p == provider.name
+                || *p == provider.qualified_name()
+                || p.strip_prefix('@') == Some(provider.id.as_str())
+        })
+    }
+
+    pub fn shared(&self) -> Vec<String> {
+        match &self.share {
+            Some(share) => share.clone(),
+            None => DEFAULT_SHARED.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+/// `[backup]`：写入前的自动备份与保留策略
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BackupConfig {
+    /// 备份目录（默认 `<数据目录>/ccstart/backups`）
+    #[serde(default)]
+    pub dir: Option<String>,
+    /// 每种创建原因保留的快照数量（默认 10）
+    #[serde(default)]
+    pub keep: Option<usize>,
+    /// 写入数据库前是否自动备份（默认开启）
+    #[serde(default)]
+    pub auto: Option<bool>,
+}
+
+/// 命名 home：可只写路径，也可分别指定数据库与缓存目录
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum HomeEntry {
+    Path(String),
+    Detailed {
+        path: String,
+        #[serde(default)]
+        db: Option<String>,
+        #[serde(default)]
+        cache_dir: Option<String>,
+    },
+}
+
+impl HomeEntry {
+    pub fn path(&self) -> &str {
+        match self {
+            HomeEntry::Path(p) | HomeEntry::Detailed { path: p, .. } => p,
+        }
+    }
+
+    pub fn db(&self) -> Option<&str> {
+        match self {
+            HomeEntry::Path(_) => None,
+            HomeEntry::Detailed { db, .. } => db.as_deref(),
+        }
+    }
+
+    pub fn cache_dir(&self) -> Option<&str> {
+        match self {
+            HomeEntry::Path(_) => None,
+            HomeEntry::Detailed { cache_dir, .. } => cache_dir.as_deref(),
+        }
+    }
+}
+
+impl ConfigFile {
+    /// 配置文件路径：`CCSTART_CONFIG` 或 `<config_dir>/ccstart/config.toml`
+    pub fn path() -> AppResult<PathBuf> {
+        if let Some(path) = std::env::var_os("CCSTART_CONFIG").filter(|v| !v.is_empty()) {
+            return Ok(PathBuf::from(path));
+        }
+        Ok(dirs::config_dir()
+            .ok_or_else(|| anyhow::anyhow!("无法获取用户配置目录"))?
+            .join("ccstart/config.toml"))
+    }
+
+    /// 读取配置文件；不存在时返回空配置
+    pub fn load() -> AppResult<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self { path, ..Default::default() });
+        }
+        let content =
+            fs::read_to_string(&path).with_context(|| format!("读取失败: {}", path.display()))?;
+        let table = content
+            .parse::<toml::Table>()
+            .with_context(|| format!("解析配置文件失败: {}", path.display()))?;
+        Ok(Self { path, table })
+    }
+
+    /// 读取配置文件；失败时警告并按空配置处理（用于不应因配置文件而无法运行的路径）。
+    /// 同一进程内只读取一次
+    pub fn load_or_default() -> &'static Self {
+        static LOADED: OnceLock<ConfigFile> = OnceLock::new();
+        LOADED.get_or_init(|| {
+            Self::load().unwrap_or_else(|e| {
+                warn(&format!("{}\n提示: 本次忽略配置文件", format!("{:#}", e).trim_end()));
+                Self::default()
+            })
+        })
+    }
+
+    /// `[homes]`：命名的 cc-switch 数据目录
+    pub fn homes(&self) -> AppResult<BTreeMap<String, HomeEntry>> {
+        self.section("homes")
+    }
+
+    /// `[backup]`：无效时警告并使用默认设置
+    pub fn backup(&self) -> BackupConfig {
+        self.section_or_default("backup")
+    }
+
+    /// `[isolate]`：无效时警告并使用默认设置（不隔离）
+    pub fn isolate(&self) -> IsolateConfig {
+        self.section_or_default("isolate")
+    }
+
+    /// `[[rules]]`：`ccstart auto` 按顺序求值的规则
+    pub fn rules(&self) -> AppResult<Vec<Rule>> {
+        self.section("rules")
+    }
+
+    /// 解析一节；不存在时为默认值
+    fn section<T: DeserializeOwned + Default>(&self, key: &str) -> AppResult<T> {
+        match self.table.get(key) {
+            None => Ok(T::default()),
+            Some(value) => value.clone().try_into().map_err(|e| {
+                anyhow::anyhow!("配置文件 {} 中的 {} 无效: {}", self.path.display(), key, e.to_string().trim_end())
+            }),
+        }
+    }
+
+    fn section_or_default<T: DeserializeOwned + Default>(&self, key: &str) -> T {
+        self.section(key).unwrap_or_else(|e| {
+            warn(&format!("{:#}\n提示: 本次使用默认的 {} 设置", e, key));
+            T::default()
+        })
+    }
+}
+
+/// 动态补全时不输出警告，避免干扰 shell
+fn warn(message: &str) {
+    if std::env::var_os("COMPLETE").is_none() {
+        eprintln!("[WARN] {}", message);
+    }
+}
@@ -0,0 +1,64 @@
+use crate::db::McpServer;
+use crate::error::AppResult;
+use serde_json::{Map, Value};
+
+/// 单次运行对 MCP 服务器集合的调整（`--mcp +name` / `--mcp -name`）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum McpToggle {
+    Add(String),
+    Remove(String),
+}
+
+impl McpToggle {
+    /// 解析 `+name`、`-name`；不带前缀时视为添加
+    pub fn parse(raw: &str) -> Self {
+        if let Some(name) = raw.strip_prefix('-') {
+            McpToggle::Remove(name.to_string())
+        } else {
+            McpToggle::Add(raw.strip_prefix('+').unwrap_or(raw).to_string())
+        }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            McpToggle::Add(n) | McpToggle::Remove(n) => n,
+        }
+    }
+}
+
+/// 计算本次运行要加载的 MCP 服务器：
+/// 以 `enabled_claude` 为初始集合，再按顺序应用各个调整
+pub fn select<'a>(servers: &'a [McpServer], toggles: &[String]) -> AppResult<Vec<&'a McpServer>> {
+    let mut enabled: Vec<bool> = servers.iter().map(|s| s.enabled_claude).collect();
+
+    for raw in toggles {
+        let toggle = McpToggle::parse(raw);
+        let Some(idx) = servers.iter().position(|s| s.name == toggle.name()) else {
+            let names: Vec<&str> = servers.iter().map(|s| s.name.as_str()).collect();
+            anyhow::bail!(
+                "未找到 MCP 服务器 '{}'\n提示: 可用服务器: {}",
+                toggle.name(),
+                if names.is_empty() { "(无)".to_string() } else { names.join(", ") }
+            );
+        };
+        enabled[idx] = matches!(toggle, McpToggle::Add(_));
+    }
+
+    Ok(servers
+        .iter()
+        .zip(enabled)
+        .filter_map(|(s, on)| on.then_some(s))
+        .collect())
+}
+
+/// 生成 `claude --mcp-config` 可读取的 JSON 文档；选中的服务器配置损坏时报错，不写入 null
+pub fn render(selected: &[&McpServer]) -> AppResult<Value> {
+    let mut map = Map::new();
+    for server in selected {
+        server.check().map_err(|e| {
+            anyhow::anyhow!("{}\n提示: 在 cc-switch 中修复，或使用 `--mcp -{}` 跳过该服务器", e, server.name)
+        })?;
+        map.insert(server.name.clone(), server.server_config.clone());
+    }
+    Ok(serde_json::json!({ "mcpServers": map }))
+}
@@ -1,4 +1,17 @@
+pub mod accounts;
+pub mod bundle;
 pub mod cache;
+pub mod claude_home;
+pub mod common;
+pub mod endpoint;
+pub mod file;
+pub mod mcp;
+pub mod paths;
+pub mod pin;
+pub mod redact;
+pub mod rules;
+pub mod settings_path;
+pub mod skills;
 
 // parser 和 manager 模块已废弃（JSON 配置已迁移到 SQLite）
 
@@ -0,0 +1,236 @@
+use crate::config::file::ConfigFile;
+use crate::error::AppResult;
+use crate::utils::encoding::encode_config_name;
+use anyhow::Context;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 命令行/环境变量给出的路径覆盖项
+#[derive(Debug, Clone, Default)]
+pub struct PathOverrides {
+    /// `--home` / `CCSTART_HOME`：路径或配置文件中的命名 home
+    pub home: Option<String>,
+    /// `--db` / `CCSTART_DB`
+    pub db: Option<PathBuf>,
+    /// `--cache-dir` / `CCSTART_CACHE_DIR`
+    pub cache_dir: Option<PathBuf>,
+}
+
+/// 解析后的各类路径
+#[derive(Debug, Clone)]
+pub struct Paths {
+    /// cc-switch 数据目录
+    pub home: PathBuf,
+    /// 命名 home 的名称（使用路径或默认目录时为空）
+    pub home_name: Option<String>,
+    /// SQLite 数据库
+    pub db: PathBuf,
+    /// 旧版 config.json
+    pub legacy_json: PathBuf,
+    /// ccstart 缓存目录（生成的 settings 等）
+    pub cache_dir: PathBuf,
+    /// 数据库快照目录
+    pub backup_dir: PathBuf,
+    /// 保留的快照数量
+    pub backup_keep: usize,
+    /// 写入前是否自动备份
+    pub auto_backup: bool,
+}
+
+/// 默认保留的快照数量
+const DEFAULT_BACKUP_KEEP: usize = 10;
+
+impl Paths {
+    /// 按优先级解析：命令行/环境变量 > 配置文件中的命名 home > 默认值
+    pub fn resolve(overrides: &PathOverrides) -> AppResult<Self> {
+        let default_home = home_dir()?.join(".cc-switch");
+
+        let mut home_name = None;
+        let mut named_db = None;
+        let mut named_cache = None;
+        let home = match overrides.home.as_deref().filter(|h| !h.is_empty()) {
+            None => default_home.clone(),
+            Some(value) => {
+                // 指定了 home 时才需要 [homes]，此时配置文件错误直接报告
+                let homes = ConfigFile::load()?.homes()?;
+                match homes.get(value) {
+                    Some(entry) => {
+                        home_name = Some(value.to_string());
+                        named_db = entry.db().map(expand_tilde).transpose()?;
+                        named_cache = entry.cache_dir().map(expand_tilde).transpose()?;
+                        expand_tilde(entry.path())?
+                    }
+                    None if looks_like_path(value) => expand_tilde(value)?,
+                    None => {
+                        let names: Vec<&str> = homes.keys().map(String::as_str).collect();
+                        anyhow::bail!(
+                            "未找到命名 home '{}'\n提示: 已配置: {}（配置文件: {}）",
+                            value,
+                            if names.is_empty() { "(无)".to_string() } else { names.join(", ") },
+                            ConfigFile::path()?.display()
+                        );
+                    }
+                }
+            }
+        };
+
+        let db = overrides
+            .db
+            .clone()
+            .or(named_db)
+            .unwrap_or_else(|| home.join("cc-switch.db"));
+        let legacy_json = home.join("config.json");
+
+        // 非默认 home 使用独立的子目录，避免不同 provider 集合互相覆盖
+        let per_home = |root: PathBuf| {
+            if home == default_home {
+                return root;
+            }
+            let id = match &home_name {
+                Some(name) => encode_config_name(name),
+                None => path_id(&home),
+            };
+            root.join("homes").join(id)
+        };
+
+        let cache_dir = match overrides.cache_dir.clone().or(named_cache) {
+            Some(dir) => dir,
+            None => per_home(
+                dirs::cache_dir()
+                    .unwrap_or_else(|| default_home.join("cache"))
+                    .join("ccstart"),
+            ),
+        };
+
+        let backup = ConfigFile::load_or_default().backup();
+        let backup_dir = match backup.dir.as_deref() {
+            Some(dir) => per_home(expand_tilde(dir)?),
+            None => per_home(
+                dirs::data_local_dir()
+                    .unwrap_or_else(|| default_home.clone())
+                    .join("ccstart/backups"),
+            ),
+        };
+
+        Ok(Self {
+            home,
+            home_name,
+            db,
+            legacy_json,
+            cache_dir,
+            backup_dir,
+            backup_keep: backup.keep.unwrap_or(DEFAULT_BACKUP_KEEP).max(1),
+            auto_backup: backup.auto.unwrap_or(true),
+        })
+    }
+}
+
+/// ccstart 创建的缓存目录中的标记文件：只有带此标记的旧版缓存目录才会被自动删除
+const CACHE_MARKER: &str = ".ccstart-cache";
+
+/// 旧版缓存目录已处理过的标记（位于当前缓存目录）
+const LEGACY_CHECKED: &str = ".legacy-cache-checked";
+
+impl Paths {
+    /// 由 `update` 与启动调用：首次运行时创建带标记的缓存目录，并处理一次旧版缓存目录
+    pub fn migrate_legacy_cache(&self) {
+        let checked = self.cache_dir.join(LEGACY_CHECKED);
+        if checked.exists() {
+            return;
+        }
+        if !self.cache_dir.exists() {
+            let marker = self.cache_dir.join(CACHE_MARKER);
+            if let Err(e) = fs::create_dir_all(&self.cache_dir).and_then(|_| fs::write(&marker, "")) {
+                eprintln!("[WARN] 创建缓存目录失败: {}: {}", self.cache_dir.display(), e);
+                return;
+            }
+        }
+        match self.remove_legacy_cache() {
+            Ok(Some(legacy)) => eprintln!("[INFO] 已清理旧版缓存目录: {}", legacy.display()),
+            Ok(None) => {}
+            Err(e) => eprintln!("[WARN] {:#}", e),
+        }
+        if let Err(e) = fs::write(&checked, "") {
+            eprintln!("[WARN] 写入失败: {}: {}", checked.display(), e);
+        }
+    }
+
+    /// 旧版本使用 `~/.cc-switch/separated` 作为缓存目录，其中的 settings 含明文密钥。
+    /// 该目录位于 cc-switch 的数据目录中，只有带 ccstart 标记且全部为可重新生成的缓存时才删除
+    /// （技能仓库检出迁移到当前缓存目录），否则只提示。返回被删除的目录
+    fn remove_legacy_cache(&self) -> AppResult<Option<PathBuf>> {
+        let legacy = home_dir()?.join(".cc-switch/separated");
+        if !legacy.is_dir() || self.cache_dir.starts_with(&legacy) {
+            return Ok(None);
+        }
+        if !legacy.join(CACHE_MARKER).is_file() {
+            anyhow::bail!(
+                "发现旧版缓存目录，无法确认由 ccstart 创建，未自动删除: {}\n提示: 其中的 config-*.json 含明文密钥，确认不再需要后请手动删除",
+                legacy.display()
+            );
+        }
+
+        let read = || format!("读取失败: {}", legacy.display());
+        for entry in fs::read_dir(&legacy).with_context(read)? {
+            let name = entry.with_context(read)?.file_name();
+            let name = name.to_string_lossy();
+            let generated = matches!(
+                &*name,
+                "codex" | "gemini" | "claude-home" | "skill-repos" | "endpoint-probes.json" | CACHE_MARKER | LEGACY_CHECKED
+            ) || name.ends_with(".tmp")
+                || ((name.starts_with("config-") || name.starts_with("mcp-")) && name.ends_with(".json"));
+            if !generated {
+                anyhow::bail!(
+                    "旧版缓存目录中有无法识别的条目 '{}'，未自动删除: {}\n提示: 其中的配置文件含明文密钥，确认后请手动删除",
+                    name,
+                    legacy.display()
+                );
+            }
+        }
+
+        let repos = legacy.join("skill-repos");
+        let target = self.cache_dir.join("skill-repos");
+        if repos.is_dir() && !target.exists() {
+            fs::create_dir_all(&self.cache_dir)
+                .with_context(|| format!("创建缓存目录失败: {}", self.cache_dir.display()))?;
+            fs::rename(&repos, &target).with_context(|| {
+                format!("迁移技能仓库失败: {} -> {}", repos.display(), target.display())
+            })?;
+        }
+        fs::remove_dir_all(&legacy).with_context(|| format!("删除失败: {}", legacy.display()))?;
+        Ok(Some(legacy))
+    }
+}
+
+fn home_dir() -> AppResult<PathBuf> {
+    dirs::home_dir().ok_or_else(|| anyhow::anyhow!("无法获取用户主目录"))
+}
+
+/// 展开开头的 `~`
+pub fn expand_tilde(raw: &str) -> AppResult<PathBuf> {
+    if raw == "~" {
+        return home_dir();
+    }
+    if let Some(rest) = raw.strip_prefix("~/").or_else(|| raw.strip_prefix("~\\")) {
+        return Ok(home_dir()?.join(rest));
+    }
+    Ok(PathBuf::from(raw))
+}
+
+/// 含路径分隔符或以 `~`/`.` 开头的值视为路径，否则视为命名 home
+fn looks_like_path(value: &str) -> bool {
+    value.starts_with('~')
+        || value.starts_with('.')
+        || value.contains('/')
+        || value.contains('\\')
+        || Path::new(value).is_absolute()
+}
+
+/// 用路径哈希生成稳定的目录名
+fn path_id(path: &Path) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(path.to_string_lossy().as_bytes());
+    let digest = format!("{:x}", hasher.finalize());
+    digest[..12].to_string()
+}
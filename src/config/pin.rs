@@ -0,0 +1,117 @@
+use crate::db::AppType;
+use crate::error::AppResult;
+use anyhow::Context;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 目录绑定文件名：同一目录中两者都存在时以 `.ccstart.toml` 为准
+pub const PIN_FILES: &[&str] = &[".ccstart.toml", ".ccstart"];
+
+/// 目录绑定（`.ccstart` / `.ccstart.toml`），类似 `.nvmrc` 固定仓库使用的配置
+///
+/// `.ccstart` 也可以只写一行配置名称
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PinFile {
+    /// 配置名称（与 `ccstart <name>` 相同的解析规则）
+    pub provider: String,
+    /// 目标应用（默认 claude）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app: Option<String>,
+    /// 追加在命令行参数之前的默认参数
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// MCP 调整（`+name` / `-name`），先于命令行的 `--mcp` 生效
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mcp: Vec<String>,
+    /// 深度合并到 provider settings 之上的字段
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settings: Option<toml::Table>,
+}
+
+/// 找到的绑定文件
+#[derive(Debug, Clone)]
+pub struct Pin {
+    pub path: PathBuf,
+    pub file: PinFile,
+}
+
+impl PinFile {
+    pub fn app_type(&self) -> AppResult<AppType> {
+        match &self.app {
+            None => Ok(AppType::Claude),
+            Some(app) => AppType::from_str(app, true)
+                .map_err(|_| anyhow::anyhow!("不支持的应用类型 '{}'", app)),
+        }
+    }
+
+    /// settings 覆盖转换为 JSON
+    pub fn settings_json(&self) -> AppResult<Option<Value>> {
+        self.settings
+            .as_ref()
+            .map(|table| serde_json::to_value(table).with_context(|| "settings 无法转换为 JSON"))
+            .transpose()
+    }
+
+    fn parse(path: &Path, text: &str) -> AppResult<Self> {
+        let parsed = toml::from_str::<Self>(text);
+        // `.ccstart` 允许只写配置名称（首个非空、非注释行）
+        if parsed.is_err() && path.file_name().is_some_and(|n| n == ".ccstart") {
+            let mut lines = text
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty() && !l.starts_with('#'));
+            if let (Some(name), None) = (lines.next(), lines.next())
+                && !name.contains('=')
+            {
+                return Ok(Self {
+                    provider: name.trim_matches('"').to_string(),
+                    ..Default::default()
+                });
+            }
+        }
+        let file = parsed.with_context(|| format!("目录绑定文件格式错误: {}", path.display()))?;
+        if file.provider.trim().is_empty() {
+            anyhow::bail!("目录绑定文件缺少 provider: {}", path.display());
+        }
+        file.app_type()
+            .with_context(|| format!("目录绑定文件无效: {}", path.display()))?;
+        Ok(file)
+    }
+
+    pub fn to_toml(&self) -> AppResult<String> {
+        let body = toml::to_string(self).with_context(|| "序列化目录绑定失败")?;
+        Ok(format!(
+            "# ccstart 目录绑定：在此目录及子目录中运行 `ccstart` 或 `ccstart .` 时使用\n{}",
+            body
+        ))
+    }
+}
+
+impl Pin {
+    /// 从 `start` 向上查找最近的绑定文件
+    pub fn find(start: &Path) -> AppResult<Option<Self>> {
+        for dir in start.ancestors() {
+            for name in PIN_FILES {
+                let path = dir.join(name);
+                if !path.is_file() {
+                    continue;
+                }
+                let text = fs::read_to_string(&path)
+                    .with_context(|| format!("读取失败: {}", path.display()))?;
+                let file = PinFile::parse(&path, &text)?;
+                return Ok(Some(Self { path, file }));
+            }
+        }
+        Ok(None)
+    }
+
+    /// 从当前工作目录向上查找
+    pub fn find_from_cwd() -> AppResult<Option<Self>> {
+        let cwd = std::env::current_dir().with_context(|| "无法获取当前工作目录")?;
+        Self::find(&cwd)
+    }
+}
@@ -0,0 +1,343 @@
+use crate::config::paths::expand_tilde;
+use crate::config::pin::PinFile;
+use crate::db::AppType;
+use crate::error::AppResult;
+use anyhow::Context;
+use chrono::{Local, NaiveTime, Timelike};
+use serde::Deserialize;
+use std::cell::OnceCell;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// `[[rules]]`：按上下文自动选择 provider（`ccstart auto`）
+///
+/// 所有已设置的条件都满足时命中；不设置任何条件的规则总是命中，可放在最后作为默认
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    /// 规则名称（仅用于 `--explain` 显示）
+    #[serde(default)]
+    pub name: Option<String>,
+    /// 当前目录或其任一上级目录匹配的 glob（`*`、`?` 不跨 `/`，`**` 跨目录）
+    #[serde(default)]
+    pub cwd: Option<String>,
+    /// git remote 的 `host/org[/repo]` 前缀，可含 glob
+    #[serde(default)]
+    pub remote: Option<String>,
+    /// 本地时间窗口 `HH:MM-HH:MM`（结束早于开始时跨午夜）
+    #[serde(default)]
+    pub time: Option<String>,
+    /// 环境变量：`NAME` 表示已设置，`NAME=value` 表示值相等
+    #[serde(default)]
+    pub env: Option<String>,
+    /// 命中后使用的配置名称
+    pub provider: String,
+    /// 目标应用（默认 claude）
+    #[serde(default)]
+    pub app: Option<String>,
+    /// 追加在命令行参数之前的默认参数
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// MCP 调整（`+name` / `-name`），先于命令行的 `--mcp` 生效
+    #[serde(default)]
+    pub mcp: Vec<String>,
+    /// 深度合并到 provider settings 之上的字段
+    #[serde(default)]
+    pub settings: Option<toml::Table>,
+}
+
+/// 单个条件的判断结果
+#[derive(Debug, Clone)]
+pub struct Check {
+    /// 条件描述，如 `cwd ~/work/**`
+    pub condition: String,
+    pub matched: bool,
+    /// 当前环境中的实际值
+    pub actual: String,
+}
+
+/// 规则求值所需的环境；git remote 在首次需要时读取
+pub struct RuleContext {
+    cwd: PathBuf,
+    now: NaiveTime,
+    remotes: OnceCell<Vec<String>>,
+}
+
+impl RuleContext {
+    pub fn current() -> AppResult<Self> {
+        Ok(Self {
+            cwd: std::env::current_dir().with_context(|| "无法获取当前工作目录")?,
+            now: Local::now().time(),
+            remotes: OnceCell::new(),
+        })
+    }
+
+    fn remotes(&self) -> &[String] {
+        self.remotes.get_or_init(|| git_remotes(&self.cwd))
+    }
+}
+
+impl Rule {
+    /// 显示用的标签：`#序号` 加可选名称
+    pub fn label(&self, index: usize) -> String {
+        match &self.name {
+            Some(name) => format!("#{} {}", index + 1, name),
+            None => format!("#{}", index + 1),
+        }
+    }
+
+    pub fn app_type(&self) -> AppResult<AppType> {
+        self.target().app_type()
+    }
+
+    /// 命中后的启动设置（与目录绑定文件相同）
+    pub fn target(&self) -> PinFile {
+        PinFile {
+            provider: self.provider.clone(),
+            app: self.app.clone(),
+            args: self.args.clone(),
+            mcp: self.mcp.clone(),
+            settings: self.settings.clone(),
+        }
+    }
+
+    /// 逐项判断规则的条件
+    pub fn evaluate(&self, ctx: &RuleContext) -> AppResult<Vec<Check>> {
+        let mut checks = Vec::new();
+
+        if let Some(pattern) = &self.cwd {
+            let expanded = normalize_path(&expand_tilde(pattern)?);
+            let matched = ctx
+                .cwd
+                .ancestors()
+                .any(|dir| glob_match(&expanded, &normalize_path(dir)));
+            checks.push(Check {
+                condition: format!("cwd {}", pattern),
+                matched,
+                actual: ctx.cwd.display().to_string(),
+            });
+        }
+
+        if let Some(pattern) = &self.remote {
+            let pattern = pattern.trim_end_matches('/').to_lowercase();
+            let remotes = ctx.remotes();
+            let matched = remotes.iter().any(|url| {
+                glob_match(&pattern, url) || glob_match(&format!("{}/**", pattern), url)
+            });
+            checks.push(Check {
+                condition: format!("remote {}", pattern),
+                matched,
+                actual: if remotes.is_empty() { "无 git remote".to_string() } else { remotes.join(", ") },
+            });
+        }
+
+        if let Some(window) = &self.time {
+            let (start, end) = parse_window(window)?;
+            let now = ctx.now;
+            let matched = in_window(start, end, now);
+            checks.push(Check {
+                condition: format!("time {}", window),
+                matched,
+                actual: format!("{:02}:{:02}", now.hour(), now.minute()),
+            });
+        }
+
+        if let Some(spec) = &self.env {
+            let (key, expected) = match spec.split_once('=') {
+                Some((key, value)) => (key.trim(), Some(value)),
+                None => (spec.trim(), None),
+            };
+            let value = std::env::var(key).ok();
+            let matched = match expected {
+                Some(expected) => value.as_deref() == Some(expected),
+                None => value.is_some(),
+            };
+            checks.push(Check {
+                condition: format!("env {}", spec),
+                matched,
+                actual: match &value {
+                    Some(_) if expected.is_none() => format!("{} 已设置", key),
+                    Some(v) => format!("{}={}", key, v),
+                    None => format!("{} 未设置", key),
+                },
+            });
+        }
+
+        Ok(checks)
+    }
+}
+
+/// 解析 `HH:MM-HH:MM`
+fn parse_window(window: &str) -> AppResult<(NaiveTime, NaiveTime)> {
+    let invalid = || anyhow::anyhow!("无效的时间窗口 '{}'，应为 HH:MM-HH:MM", window);
+    let (start, end) = window.split_once('-').ok_or_else(invalid)?;
+    let parse = |s: &str| NaiveTime::parse_from_str(s.trim(), "%H:%M").map_err(|_| invalid());
+    Ok((parse(start)?, parse(end)?))
+}
+
+/// `[start, end)`；结束早于开始时跨午夜
+fn in_window(start: NaiveTime, end: NaiveTime, now: NaiveTime) -> bool {
+    if start <= end {
+        start <= now && now < end
+    } else {
+        now >= start || now < end
+    }
+}
+
+/// 路径统一使用 `/` 分隔
+fn normalize_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// 读取当前仓库的 git remote，规范为 `host/path`（小写，无 `.git` 后缀）
+fn git_remotes(cwd: &Path) -> Vec<String> {
+    let Ok(output) = Command::new("git").arg("-C").arg(cwd).args(["remote", "-v"]).output() else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+    let mut remotes = Vec::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some(url) = line.split_whitespace().nth(1)
+            && let Some(normalized) = normalize_remote(url)
+            && !remotes.contains(&normalized)
+        {
+            remotes.push(normalized);
+        }
+    }
+    remotes
+}
+
+/// 支持 `scheme://[user@]host[:port]/path` 与 `[user@]host:path` 两种形式
+fn normalize_remote(url: &str) -> Option<String> {
+    let (host, path) = match url.split_once("://") {
+        Some((_, rest)) => {
+            let (authority, path) = rest.split_once('/')?;
+            let host = authority.rsplit('@').next()?;
+            (host.split(':').next()?, path)
+        }
+        None => {
+            let (authority, path) = url.split_once(':')?;
+            (authority.rsplit('@').next()?, path)
+        }
+    };
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    if host.is_empty() {
+        return None;
+    }
+    Some(format!("{}/{}", host, path).to_lowercase())
+}
+
+/// 简单 glob：`*`、`?` 不匹配 `/`，`**` 匹配任意字符（含 `/`）
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+    let mut memo = vec![None; (p.len() + 1) * (t.len() + 1)];
+    glob_at(&p, &t, 0, 0, &mut memo)
+}
+
+/// 从模式第 `i` 个、文本第 `j` 个字符起是否匹配；按 `(i, j)` 记忆结果，
+/// 多个 `**` 时也不会指数级回溯
+fn glob_at(p: &[char], t: &[char], i: usize, j: usize, memo: &mut [Option<bool>]) -> bool {
+    let key = i * (t.len() + 1) + j;
+    if let Some(hit) = memo[key] {
+        return hit;
+    }
+    let matched = match p.get(i) {
+        None => j == t.len(),
+        Some('*') if p.get(i + 1) == Some(&'*') => {
+            let rest = i + 2;
+            // `a/**/b` 也匹配 `a/b`
+            (p.get(rest) == Some(&'/') && glob_at(p, t, rest + 1, j, memo))
+                || (j..=t.len()).any(|k| glob_at(p, t, rest, k, memo))
+        }
+        Some('*') => {
+            let mut matched = false;
+            for k in j..=t.len() {
+                if glob_at(p, t, i + 1, k, memo) {
+                    matched = true;
+                    break;
+                }
+                if t.get(k) == Some(&'/') {
+                    break;
+                }
+            }
+            matched
+        }
+        Some('?') => t.get(j).is_some_and(|c| *c != '/') && glob_at(p, t, i + 1, j + 1, memo),
+        Some(c) => t.get(j) == Some(c) && glob_at(p, t, i + 1, j + 1, memo),
+    };
+    memo[key] = Some(matched);
+    matched
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(s: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(s, "%H:%M").unwrap()
+    }
+
+    #[test]
+    fn glob_double_star_spans_directories() {
+        assert!(glob_match("a/**/b", "a/b"));
+        assert!(glob_match("a/**/b", "a/x/b"));
+        assert!(glob_match("a/**/b", "a/x/y/b"));
+        assert!(!glob_match("a/**/b", "a/xb"));
+        assert!(glob_match("/home/*/work/**", "/home/me/work/repo/src"));
+    }
+
+    #[test]
+    fn glob_single_star_and_question_stay_in_segment() {
+        assert!(glob_match("a/*", "a/b"));
+        assert!(!glob_match("a/*", "a/b/c"));
+        assert!(glob_match("a/?c", "a/bc"));
+        assert!(!glob_match("a?c", "a/c"));
+    }
+
+    #[test]
+    fn glob_many_double_stars_terminate() {
+        let pattern = "**/".repeat(12) + "z";
+        let text = "a/".repeat(60) + "b";
+        assert!(!glob_match(&pattern, &text));
+        assert!(glob_match(&pattern, &("a/".repeat(60) + "z")));
+    }
+
+    #[test]
+    fn normalize_remote_forms() {
+        assert_eq!(normalize_remote("git@host:org/repo.git").as_deref(), Some("host/org/repo"));
+        assert_eq!(
+            normalize_remote("https://user@host:443/org/repo").as_deref(),
+            Some("host/org/repo")
+        );
+        assert_eq!(
+            normalize_remote("ssh://git@Host.example:22/Org/Repo.git/").as_deref(),
+            Some("host.example/org/repo")
+        );
+        assert_eq!(normalize_remote("no-remote"), None);
+    }
+
+    #[test]
+    fn parse_window_and_midnight_crossing() {
+        let (start, end) = parse_window("22:00-06:00").unwrap();
+        assert!(in_window(start, end, time("23:30")));
+        assert!(in_window(start, end, time("00:00")));
+        assert!(in_window(start, end, time("05:59")));
+        assert!(!in_window(start, end, time("06:00")));
+        assert!(!in_window(start, end, time("12:00")));
+
+        let (start, end) = parse_window(" 09:00 - 18:00 ").unwrap();
+        assert!(in_window(start, end, time("09:00")));
+        assert!(!in_window(start, end, time("18:00")));
+    }
+
+    #[test]
+    fn parse_window_rejects_invalid() {
+        assert!(parse_window("9-5").is_err());
+        assert!(parse_window("25:00-01:00").is_err());
+        assert!(parse_window("09:00").is_err());
+    }
+}
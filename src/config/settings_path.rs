@@ -0,0 +1,295 @@
+use crate::error::{AppResult, ConfigError};
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// settings_config 中的字段路径：点分形式 `env.ANTHROPIC_MODEL`、`permissions.allow.0`，
+/// 或 JSON Pointer 形式 `/env/ANTHROPIC_MODEL`（键名包含 `.` 时使用）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettingsPath {
+    segments: Vec<String>,
+}
+
+impl SettingsPath {
+    pub fn parse(input: &str) -> AppResult<Self> {
+        let segments: Vec<String> = if let Some(pointer) = input.strip_prefix('/') {
+            pointer
+                .split('/')
+                .map(|s| s.replace("~1", "/").replace("~0", "~"))
+                .collect()
+        } else {
+            input.split('.').map(str::to_string).collect()
+        };
+        if segments.iter().any(String::is_empty) {
+            return Err(ConfigError::InvalidStructure(format!("无效的字段路径: '{}'", input)).into());
+        }
+        Ok(Self { segments })
+    }
+
+    /// 最后一级键名（用于判断是否为敏感字段）
+    pub fn leaf(&self) -> &str {
+        self.segments.last().map(String::as_str).unwrap_or_default()
+    }
+
+    /// 第一级键名
+    pub fn root(&self) -> &str {
+        self.segments.first().map(String::as_str).unwrap_or_default()
+    }
+
+    pub fn get<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        self.segments.iter().try_fold(value, |node, seg| match node {
+            Value::Object(map) => map.get(seg),
+            Value::Array(items) => seg.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        })
+    }
+
+    fn get_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value> {
+        self.segments.iter().try_fold(value, |node, seg| match node {
+            Value::Object(map) => map.get_mut(seg),
+            Value::Array(items) => seg.parse::<usize>().ok().and_then(|i| items.get_mut(i)),
+            _ => None,
+        })
+    }
+
+    /// 取得（必要时创建）路径对应的节点；中间缺失的层级创建为对象
+    fn get_or_create<'v>(&self, value: &'v mut Value) -> AppResult<&'v mut Value> {
+        let mut node = value;
+        for seg in &self.segments {
+            if node.is_null() {
+                *node = Value::Object(Map::new());
+            }
+            node = match node {
+                Value::Object(map) => map.entry(seg.clone()).or_insert(Value::Null),
+                Value::Array(items) => {
+                    let len = items.len();
+                    match seg.parse::<usize>() {
+                        Ok(i) if i < len => &mut items[i],
+                        _ => return Err(self.error(&format!("数组下标 '{}' 超出范围（长度 {}）", seg, len))),
+                    }
+                }
+                _ => return Err(self.error(&format!("'{}' 的上级不是对象或数组", seg))),
+            };
+        }
+        Ok(node)
+    }
+
+    fn error(&self, msg: &str) -> anyhow::Error {
+        ConfigError::InvalidStructure(format!("{}: {}", self, msg)).into()
+    }
+}
+
+impl fmt::Display for SettingsPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.segments.join("."))
+    }
+}
+
+/// 字段修改操作
+#[derive(Debug, Clone)]
+pub enum Edit {
+    /// `path=value`
+    Set(SettingsPath, Value),
+    /// `path+=value`：向数组追加（已存在则跳过）
+    Append(SettingsPath, Value),
+    /// `path-=value`：从数组移除所有相等的元素
+    Remove(SettingsPath, Value),
+    /// 删除字段或数组元素
+    Unset(SettingsPath),
+}
+
+/// 值的解析方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValueKind {
+    /// 按目标字段推断：已有字符串或 `env` 下的字段保持字符串，否则尝试按 JSON 解析
+    #[default]
+    Auto,
+    /// 始终作为字符串
+    String,
+    /// 必须是合法 JSON
+    Json,
+}
+
+impl Edit {
+    /// 解析 `<path> <value>`，或单个参数 `<path>+=<value>` / `<path>-=<value>` / `<path>=<value>`
+    pub fn parse(path: &str, value: Option<&str>, kind: ValueKind, current: &Value) -> AppResult<Self> {
+        let (path, op, raw) = match value {
+            Some(raw) => (path, "=", raw),
+            // 在第一个 `=` 处分开，值中的 `+=`、`-=` 原样保留
+            None => {
+                let (left, raw) = path.split_once('=').ok_or_else(|| {
+                    anyhow::anyhow!("缺少要设置的值: 使用 `<path> <value>` 或 `<path>+=<value>`")
+                })?;
+                match left.strip_suffix('+').map(|p| (p, "+=")) {
+                    Some((path, op)) => (path, op, raw),
+                    None => match left.strip_suffix('-') {
+                        Some(path) => (path, "-=", raw),
+                        None => (left, "=", raw),
+                    },
+                }
+            }
+        };
+        let path = SettingsPath::parse(path)?;
+        match op {
+            "+=" | "-=" => {
+                // 数组元素的类型参照已有元素
+                let sample = path.get(current).and_then(|v| v.as_array()).and_then(|a| a.first());
+                let value = parse_value(raw, kind, sample, &path)?;
+                Ok(if op == "+=" {
+                    Edit::Append(path, value)
+                } else {
+                    Edit::Remove(path, value)
+                })
+            }
+            _ => {
+                let value = parse_value(raw, kind, path.get(current), &path)?;
+                Ok(Edit::Set(path, value))
+            }
+        }
+    }
+
+    /// 应用到 settings，返回是否有变化
+    pub fn apply(&self, settings: &mut Value) -> AppResult<bool> {
+        match self {
+            Edit::Set(path, value) => {
+                let node = path.get_or_create(settings)?;
+                if node == value {
+                    return Ok(false);
+                }
+                *node = value.clone();
+                Ok(true)
+            }
+            Edit::Append(path, value) => {
+                let node = path.get_or_create(settings)?;
+                if node.is_null() {
+                    *node = Value::Array(Vec::new());
+                }
+                let Value::Array(items) = node else {
+                    return Err(path.error("不是数组，无法使用 +="));
+                };
+                if items.contains(value) {
+                    return Ok(false);
+                }
+                items.push(value.clone());
+                Ok(true)
+            }
+            Edit::Remove(path, value) => {
+                let Some(node) = path.get_mut(settings) else {
+                    return Ok(false);
+                };
+                let Value::Array(items) = node else {
+                    return Err(path.error("不是数组，无法使用 -="));
+                };
+                let before = items.len();
+                items.retain(|item| item != value);
+                Ok(items.len() != before)
+            }
+            Edit::Unset(path) => {
+                let (last, parents) = path.segments.split_last().expect("路径非空");
+                let parent = SettingsPath {
+                    segments: parents.to_vec(),
+                };
+                Ok(match parent.get_mut(settings) {
+                    Some(Value::Object(map)) => map.remove(last).is_some(),
+                    Some(Value::Array(items)) => match last.parse::<usize>() {
+                        Ok(i) if i < items.len() => {
+                            items.remove(i);
+                            true
+                        }
+                        _ => false,
+                    },
+                    _ => false,
+                })
+            }
+        }
+    }
+}
+
+/// 按类型规则解析命令行中的值；`existing` 为目标位置的现有值（或数组的已有元素）
+fn parse_value(raw: &str, kind: ValueKind, existing: Option<&Value>, path: &SettingsPath) -> AppResult<Value> {
+    match kind {
+        ValueKind::String => Ok(Value::String(raw.to_string())),
+        ValueKind::Json => serde_json::from_str(raw)
+            .map_err(|e| path.error(&format!("值不是合法的 JSON: {}", e))),
+        ValueKind::Auto => {
+            // env 中的值都是字符串（会被导出为环境变量）
+            if path.root() == "env" || matches!(existing, Some(Value::String(_))) {
+                return Ok(Value::String(raw.to_string()));
+            }
+            let parsed = serde_json::from_str::<Value>(raw).ok();
+            match (existing, parsed) {
+                (Some(Value::Number(_)), Some(v @ Value::Number(_)))
+                | (Some(Value::Bool(_)), Some(v @ Value::Bool(_))) => Ok(v),
+                (Some(old @ (Value::Number(_) | Value::Bool(_))), _) => Err(path.error(&format!(
+                    "现有值为 {}，'{}' 类型不符（使用 --string 或 --json 强制指定）",
+                    old, raw
+                ))),
+                (_, Some(v)) => Ok(v),
+                (_, None) => Ok(Value::String(raw.to_string())),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parse(arg: &str) -> Edit {
+        Edit::parse(arg, None, ValueKind::Auto, &Value::Null).unwrap()
+    }
+
+    #[test]
+    fn splits_at_first_equals() {
+        match parse("env.X=a+=b") {
+            Edit::Set(path, value) => {
+                assert_eq!(path.to_string(), "env.X");
+                assert_eq!(value, json!("a+=b"));
+            }
+            other => panic!("应为 Set: {:?}", other),
+        }
+        match parse("env.X=a=b") {
+            Edit::Set(_, value) => assert_eq!(value, json!("a=b")),
+            other => panic!("应为 Set: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_append_and_remove() {
+        match parse("permissions.allow+=Bash(ls)") {
+            Edit::Append(path, value) => {
+                assert_eq!(path.to_string(), "permissions.allow");
+                assert_eq!(value, json!("Bash(ls)"));
+            }
+            other => panic!("应为 Append: {:?}", other),
+        }
+        match parse("permissions.allow-=x=y") {
+            Edit::Remove(path, value) => {
+                assert_eq!(path.to_string(), "permissions.allow");
+                assert_eq!(value, json!("x=y"));
+            }
+            other => panic!("应为 Remove: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn two_argument_form_keeps_value() {
+        let edit = Edit::parse("env.X", Some("+=1"), ValueKind::Auto, &Value::Null).unwrap();
+        assert!(matches!(edit, Edit::Set(_, Value::String(ref v)) if v == "+=1"));
+    }
+
+    #[test]
+    fn rejects_missing_value_and_empty_segments() {
+        assert!(Edit::parse("env.X", None, ValueKind::Auto, &Value::Null).is_err());
+        assert!(Edit::parse("env..X=1", None, ValueKind::Auto, &Value::Null).is_err());
+    }
+
+    #[test]
+    fn apply_append_is_idempotent() {
+        let mut settings = json!({"permissions": {"allow": ["a"]}});
+        let edit = parse("permissions.allow+=b");
+        assert!(edit.apply(&mut settings).unwrap());
+        assert!(!edit.apply(&mut settings).unwrap());
+        assert_eq!(settings, json!({"permissions": {"allow": ["a", "b"]}}));
+    }
+}
@@ -0,0 +1,143 @@
+use crate::config::cache::CacheManager;
+use crate::config::claude_home;
+use crate::db::{Database, Skill, SkillRepo};
+use crate::error::AppResult;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 在仓库检出目录中查找技能目录的最大深度
+const MAX_SEARCH_DEPTH: usize = 3;
+
+/// 解析后的技能
+#[derive(Debug, Clone)]
+pub struct ResolvedSkill {
+    /// skills 表中的原始 key
+    pub key: String,
+    /// 技能目录名（即链接到 skills/ 下的名称）
+    pub dir_name: String,
+    /// 所属仓库 `<owner>/<repo>`（key 中未指定时为空）
+    pub repo: Option<String>,
+    /// 本地路径；未找到时为 None
+    pub path: Option<PathBuf>,
+}
+
+/// 技能解析器：只在本地磁盘上查找，不访问网络
+pub struct SkillResolver {
+    repos_dir: PathBuf,
+    main_skills_dir: PathBuf,
+}
+
+impl SkillResolver {
+    /// `repos_dir` 为仓库检出根目录（`<owner>/<repo>`），`main_skills_dir` 为主配置的 skills 目录
+    pub fn new(repos_dir: PathBuf, main_skills_dir: PathBuf) -> Self {
+        Self {
+            repos_dir,
+            main_skills_dir,
+        }
+    }
+
+    /// 使用缓存目录下的仓库检出与主 Claude 配置中的 skills 目录
+    pub fn from_cache(cache: &CacheManager) -> AppResult<Self> {
+        Ok(Self::new(
+            cache.skill_repos_dir(),
+            claude_home::main_config_dir()?.join("skills"),
+        ))
+    }
+
+    /// 从数据库读取已安装技能与仓库并解析
+    pub fn load(&self, db: &Database) -> AppResult<(Vec<SkillRepo>, Vec<ResolvedSkill>)> {
+        let skills = db.skills().list_installed()?;
+        let repos = db.skills().list_repos()?;
+        let resolved = self.resolve(&skills, &repos);
+        Ok((repos, resolved))
+    }
+
+    /// 仓库的本地检出目录
+    pub fn checkout_dir(&self, repo: &SkillRepo) -> PathBuf {
+        self.repos_dir.join(&repo.owner).join(&repo.name)
+    }
+
+    /// 解析所有已安装技能的本地路径
+    pub fn resolve(&self, skills: &[Skill], repos: &[SkillRepo]) -> Vec<ResolvedSkill> {
+        skills
+            .iter()
+            .map(|skill| {
+                let (repo, dir_name) = split_key(&skill.key);
+                let path = self.locate(repo.as_deref(), &dir_name, repos);
+                ResolvedSkill {
+                    key: skill.key.clone(),
+                    dir_name,
+                    repo,
+                    path,
+                }
+            })
+            .collect()
+    }
+
+    /// 查找顺序：指定仓库的检出 → 主 skills 目录 → 其它已启用仓库的检出
+    fn locate(&self, repo: Option<&str>, dir_name: &str, repos: &[SkillRepo]) -> Option<PathBuf> {
+        if let Some(repo) = repo
+            && let Some(r) = repos.iter().find(|r| format!("{}/{}", r.owner, r.name) == repo)
+            && let Some(found) = find_skill_dir(&self.checkout_dir(r), dir_name, 0)
+        {
+            return Some(found);
+        }
+
+        let main = self.main_skills_dir.join(dir_name);
+        if is_skill_dir(&main) {
+            return Some(main);
+        }
+
+        if repo.is_none() {
+            return repos
+                .iter()
+                .filter(|r| r.enabled)
+                .find_map(|r| find_skill_dir(&self.checkout_dir(r), dir_name, 0));
+        }
+        None
+    }
+}
+
+/// 拆分 `<owner>/<repo>:<directory>` 形式的 key
+fn split_key(key: &str) -> (Option<String>, String) {
+    match key.split_once(':') {
+        Some((repo, dir)) if repo.contains('/') => {
+            let dir_name = dir.rsplit('/').next().unwrap_or(dir).to_string();
+            (Some(repo.to_string()), dir_name)
+        }
+        _ => (None, key.to_string()),
+    }
+}
+
+/// 技能目录以包含 SKILL.md 为准
+fn is_skill_dir(path: &Path) -> bool {
+    path.join("SKILL.md").is_file()
+}
+
+/// 在仓库目录中按名称递归查找技能目录
+fn find_skill_dir(root: &Path, dir_name: &str, depth: usize) -> Option<PathBuf> {
+    let direct = root.join(dir_name);
+    if is_skill_dir(&direct) {
+        return Some(direct);
+    }
+    if depth >= MAX_SEARCH_DEPTH {
+        return None;
+    }
+
+    let mut children: Vec<PathBuf> = fs::read_dir(root)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.is_dir()
+                && !p
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.starts_with('.'))
+        })
+        .collect();
+    children.sort();
+    children
+        .iter()
+        .find_map(|child| find_skill_dir(child, dir_name, depth + 1))
+}
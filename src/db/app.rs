@@ -0,0 +1,48 @@
+use std::fmt;
+
+/// cc-switch 中的应用类型（对应 providers.app_type 列）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, clap::ValueEnum)]
+pub enum AppType {
+    /// Claude Code CLI
+    #[default]
+    Claude,
+    /// OpenAI Codex CLI
+    Codex,
+    /// Google Gemini CLI
+    Gemini,
+}
+
+impl AppType {
+    /// 数据库中存储的 app_type 值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AppType::Claude => "claude",
+            AppType::Codex => "codex",
+            AppType::Gemini => "gemini",
+        }
+    }
+
+    /// 面向用户的显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            AppType::Claude => "Claude",
+            AppType::Codex => "Codex",
+            AppType::Gemini => "Gemini",
+        }
+    }
+
+    /// 启动时执行的命令名
+    pub fn program(&self) -> &'static str {
+        match self {
+            AppType::Claude => "claude",
+            AppType::Codex => "codex",
+            AppType::Gemini => "gemini",
+        }
+    }
+}
+
+impl fmt::Display for AppType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
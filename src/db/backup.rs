@@ -0,0 +1,187 @@
+use super::Database;
+use crate::error::AppResult;
+use anyhow::Context;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 快照文件名前缀：`cc-switch-<UTC 时间>-<原因>.db`
+const PREFIX: &str = "cc-switch-";
+const SUFFIX: &str = ".db";
+
+/// 数据库快照目录，按时间戳命名，每种创建原因只保留最近的若干份
+#[derive(Debug, Clone)]
+pub struct BackupStore {
+    dir: PathBuf,
+    keep: usize,
+}
+
+/// 一份已存在的快照
+#[derive(Debug, Clone)]
+pub struct BackupEntry {
+    pub path: PathBuf,
+    /// 文件名（可直接用于 `restore`）
+    pub name: String,
+    /// UTC 时间，如 `2026-10-18 11:02:03`
+    pub created: String,
+    /// 创建原因：`manual`、`auto`、`pre-restore`
+    pub reason: String,
+    pub size: u64,
+}
+
+impl BackupStore {
+    pub fn new(dir: PathBuf, keep: usize) -> Self {
+        Self { dir, keep }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 使用 SQLite 在线备份 API 生成一致的快照，并按保留数量清理旧快照
+    pub fn create(&self, db: &Database, reason: &str) -> AppResult<PathBuf> {
+        self.create_protecting(db, reason, None)
+    }
+
+    /// 同 [`create`](Self::create)，但清理时保留 `protect`（如正要恢复的快照）
+    pub fn create_protecting(&self, db: &Database, reason: &str, protect: Option<&Path>) -> AppResult<PathBuf> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("创建备份目录失败: {}", self.dir.display()))?;
+
+        let stamp = utc_stamp(SystemTime::now());
+        let mut path = self.dir.join(format!("{}{}-{}{}", PREFIX, stamp, reason, SUFFIX));
+        let mut n = 2;
+        while path.exists() {
+            path = self.dir.join(format!("{}{}-{}-{}{}", PREFIX, stamp, reason, n, SUFFIX));
+            n += 1;
+        }
+
+        db.backup_to(&path)?;
+        self.prune(protect)?;
+        Ok(path)
+    }
+
+    /// 列出快照，最新的在前
+    pub fn list(&self) -> AppResult<Vec<BackupEntry>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut entries = Vec::new();
+        let mut modified = Vec::new();
+        for entry in fs::read_dir(&self.dir)
+            .with_context(|| format!("读取备份目录失败: {}", self.dir.display()))?
+        {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let Some((date, time, reason)) = parse_name(&name) else {
+                continue;
+            };
+            entries.push(BackupEntry {
+                path: entry.path(),
+                created: format!(
+                    "{}-{}-{} {}:{}:{}",
+                    &date[..4],
+                    &date[4..6],
+                    &date[6..],
+                    &time[..2],
+                    &time[2..4],
+                    &time[4..]
+                ),
+                // 同一秒内的重复快照带有 `-2`、`-3` 后缀
+                reason: match reason.rsplit_once('-') {
+                    Some((base, n)) if n.bytes().all(|b| b.is_ascii_digit()) => base.to_string(),
+                    _ => reason.to_string(),
+                },
+                size: metadata.len(),
+                name,
+            });
+            modified.push(metadata.modified().unwrap_or(UNIX_EPOCH));
+        }
+        // 同一秒内的快照按修改时间区分先后
+        let mut order: Vec<usize> = (0..entries.len()).collect();
+        order.sort_by(|&a, &b| (&entries[b].created, modified[b]).cmp(&(&entries[a].created, modified[a])));
+        let entries = order.into_iter().map(|i| entries[i].clone()).collect();
+        Ok(entries)
+    }
+
+    /// 解析 `restore` 的参数：`latest`、`list` 中的序号（从 1 开始）、文件名或路径
+    pub fn find(&self, input: &str) -> AppResult<PathBuf> {
+        let entries = self.list()?;
+        if input == "latest" {
+            return entries
+                .first()
+                .map(|e| e.path.clone())
+                .ok_or_else(|| anyhow::anyhow!("没有可用的快照（备份目录: {}）", self.dir.display()));
+        }
+        if let Ok(index) = input.parse::<usize>()
+            && let Some(entry) = index.checked_sub(1).and_then(|i| entries.get(i))
+        {
+            return Ok(entry.path.clone());
+        }
+        if let Some(entry) = entries.iter().find(|e| e.name == input) {
+            return Ok(entry.path.clone());
+        }
+        let path = PathBuf::from(input);
+        if path.is_file() {
+            return Ok(path);
+        }
+        anyhow::bail!(
+            "未找到快照: {}\n提示: 使用 `ccstart backup --list` 查看可用快照",
+            input
+        )
+    }
+
+    /// 按创建原因分别删除超出保留数量的旧快照：频繁的自动快照不会挤掉手动快照
+    fn prune(&self, protect: Option<&Path>) -> AppResult<()> {
+        let protect = protect.and_then(|p| fs::canonicalize(p).ok());
+        let mut kept: HashMap<String, usize> = HashMap::new();
+        for entry in self.list()? {
+            let count = kept.entry(entry.reason.clone()).or_default();
+            *count += 1;
+            if *count <= self.keep || (protect.is_some() && fs::canonicalize(&entry.path).ok() == protect) {
+                continue;
+            }
+            fs::remove_file(&entry.path)
+                .with_context(|| format!("删除旧快照失败: {}", entry.path.display()))?;
+        }
+        Ok(())
+    }
+}
+
+/// 拆分快照文件名，返回（日期、时间、原因）
+fn parse_name(name: &str) -> Option<(&str, &str, &str)> {
+    let stem = name.strip_prefix(PREFIX)?.strip_suffix(SUFFIX)?;
+    let (date, rest) = stem.split_once('-')?;
+    let (time, reason) = rest.split_once('-')?;
+    let digits = |s: &str, len: usize| s.len() == len && s.bytes().all(|b| b.is_ascii_digit());
+    (digits(date, 8) && digits(time, 6)).then_some((date, time, reason))
+}
+
+/// UTC 时间戳 `YYYYMMDD-HHMMSS`（按名称排序即按时间排序）
+fn utc_stamp(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+
+    // 公历日期换算（Howard Hinnant 的 civil_from_days）
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
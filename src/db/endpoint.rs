@@ -0,0 +1,39 @@
+use crate::error::AppResult;
+use anyhow::Context;
+use rusqlite::params;
+
+use super::{AppType, Database};
+
+/// Provider 端点数据访问对象（对应 provider_endpoints 表）
+pub struct EndpointDao<'a> {
+    db: &'a Database,
+}
+
+impl<'a> EndpointDao<'a> {
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// 获取 provider 记录的所有端点 URL（按添加顺序）
+    pub fn list_urls(&self, app: AppType, provider_id: &str) -> AppResult<Vec<String>> {
+        if !self.db.capabilities()?.table_available("provider_endpoints")? {
+            return Ok(Vec::new());
+        }
+        let conn = self.db.connect()?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT url FROM provider_endpoints
+                 WHERE app_type = ?1 AND provider_id = ?2
+                 ORDER BY added_at, id",
+            )
+            .with_context(|| "准备查询语句失败")?;
+
+        let urls: Vec<String> = stmt
+            .query_map(params![app.as_str(), provider_id], |row| row.get(0))
+            .with_context(|| "执行查询失败")?
+            .collect::<Result<_, _>>()
+            .with_context(|| "读取查询结果失败")?;
+
+        Ok(urls)
+    }
+}
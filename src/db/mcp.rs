@@ -0,0 +1,79 @@
+use crate::error::{AppResult, ConfigError, JsonError};
+use anyhow::Context;
+use serde_json::Value;
+
+use super::Database;
+
+/// MCP 服务器数据模型（对应 mcp_servers 表）
+#[derive(Debug, Clone)]
+pub struct McpServer {
+    #[allow(dead_code)]
+    pub id: String,
+    pub name: String,
+    pub server_config: Value,
+    /// server_config 无法解析时的错误（此时 server_config 为 null，不可写入 MCP 配置）
+    pub load_error: Option<JsonError>,
+    pub description: Option<String>,
+    pub enabled_claude: bool,
+}
+
+impl McpServer {
+    /// 检查 server_config 是否可用；无法解析时返回带服务器名称的错误
+    pub fn check(&self) -> AppResult<()> {
+        match &self.load_error {
+            Some(err) => Err(ConfigError::InvalidStructure(format!(
+                "MCP 服务器 '{}' 的 server_config 无法解析（{}）",
+                self.name, err
+            ))
+            .into()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// MCP 服务器数据访问对象
+pub struct McpDao<'a> {
+    db: &'a Database,
+}
+
+impl<'a> McpDao<'a> {
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// 获取所有 MCP 服务器（按名称排序）
+    pub fn list_all(&self) -> AppResult<Vec<McpServer>> {
+        if !self.db.capabilities()?.table_available("mcp_servers")? {
+            return Ok(Vec::new());
+        }
+        let conn = self.db.connect()?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, name, server_config, description, enabled_claude
+                 FROM mcp_servers
+                 ORDER BY name",
+            )
+            .with_context(|| "准备查询语句失败")?;
+
+        let iter = stmt
+            .query_map([], |row| {
+                let config_str: String = row.get(2)?;
+                let (server_config, load_error) = match serde_json::from_str(&config_str) {
+                    Ok(value) => (value, None),
+                    Err(e) => (Value::Null, Some(JsonError::from_serde(&e))),
+                };
+                Ok(McpServer {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    server_config,
+                    load_error,
+                    description: row.get(3)?,
+                    enabled_claude: row.get(4)?,
+                })
+            })
+            .with_context(|| "执行查询失败")?;
+
+        iter.collect::<Result<Vec<_>, _>>()
+            .with_context(|| "读取查询结果失败")
+    }
+}
@@ -1,23 +1,73 @@
+mod app;
+mod backup;
+mod endpoint;
+mod mcp;
+mod prompt;
 mod provider;
+mod schema;
+mod settings;
+mod skill;
 
-pub use provider::{Provider, ProviderDao};
+pub use app::AppType;
+pub use backup::BackupStore;
+pub use endpoint::EndpointDao;
+pub use mcp::{McpDao, McpServer};
+pub use prompt::PromptDao;
+pub use provider::{validate_settings, ImportItem, NewProvider, Provider, ProviderDao};
+pub use schema::Capabilities;
+pub use settings::SettingsDao;
+pub use skill::{Skill, SkillDao, SkillRepo};
 
 use crate::error::AppResult;
 use anyhow::Context;
-use rusqlite::{Connection, OpenFlags};
-use std::path::PathBuf;
+use rusqlite::backup::{Backup, StepResult};
+use rusqlite::{Connection, DatabaseName, ErrorCode, OpenFlags, Transaction, TransactionBehavior};
+use std::cell::{Cell, OnceCell};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
 
-/// 数据库访问结构
+/// cc-switch 正在写入时，单条语句等待锁释放的时间
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 超过 busy timeout 后的整体重试次数
+const BUSY_RETRIES: u32 = 3;
+
+/// 恢复快照时 cc-switch 持有锁的等待间隔与连续等待次数上限（合计约 BUSY_TIMEOUT）
+const RESTORE_PAUSE: Duration = Duration::from_millis(50);
+const RESTORE_RETRIES: u32 = 100;
+
+/// 数据库打开方式（`--db-mode`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum DbMode {
+    /// 只读打开；WAL 数据库所在目录不可写（无法创建 -shm）时自动改用 immutable
+    #[default]
+    Auto,
+    /// 以 immutable 方式打开：不加锁、不读取 -wal，适合网络/慢速文件系统上的只读快照
+    Immutable,
+}
+
+/// 数据库访问结构：持有一个按需创建的共享只读连接
 pub struct Database {
     path: PathBuf,
+    mode: DbMode,
+    shared: OnceCell<Shared>,
+    /// 写入前自动备份的快照目录；每个进程只在第一次写入前备份一次
+    backups: Option<BackupStore>,
+    backed_up: Cell<bool>,
+}
+
+/// 共享连接及其 schema 能力
+struct Shared {
+    conn: Connection,
+    caps: Capabilities,
 }
 
 impl Database {
-    /// 打开数据库（只读模式）
-    pub fn open() -> AppResult<Self> {
-        let path = dirs::home_dir()
-            .ok_or_else(|| anyhow::anyhow!("无法获取用户主目录"))?
-            .join(".cc-switch/cc-switch.db");
+    /// 打开数据库（只读模式）；连接在第一次查询时创建
+    pub fn open(path: &Path, mode: DbMode) -> AppResult<Self> {
+        let path = path.to_path_buf();
 
         if !path.exists() {
             anyhow::bail!(
@@ -26,17 +76,296 @@ impl Database {
             );
         }
 
-        Ok(Self { path })
+        Ok(Self {
+            path,
+            mode,
+            shared: OnceCell::new(),
+            backups: None,
+            backed_up: Cell::new(false),
+        })
+    }
+
+    /// 启用写入前的自动备份
+    pub fn set_backups(&mut self, store: BackupStore) {
+        self.backups = Some(store);
+    }
+
+    /// 数据库文件路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 打开时探测到的 schema 能力
+    pub fn capabilities(&self) -> AppResult<&Capabilities> {
+        Ok(&self.shared()?.caps)
+    }
+
+    /// 获取共享只读连接
+    pub fn connect(&self) -> AppResult<&Connection> {
+        Ok(&self.shared()?.conn)
+    }
+
+    /// 开启读快照：guard 存活期间的所有查询看到同一份一致的数据。
+    /// 启动子进程前应释放，避免长时间持有共享锁阻塞 cc-switch 写入
+    pub fn snapshot(&self) -> AppResult<Snapshot<'_>> {
+        let conn = self.connect()?;
+        if !conn.is_autocommit() {
+            // 已处于快照中（嵌套调用）
+            return Ok(Snapshot { conn, active: false });
+        }
+
+        conn.execute_batch("BEGIN").with_context(|| "开启读事务失败")?;
+        let snapshot = Snapshot { conn, active: true };
+        // BEGIN 为延迟事务，第一次读取时才真正建立快照
+        with_busy_retry(|| {
+            conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| row.get::<_, i64>(0))
+                .map_err(anyhow::Error::from)
+        })
+        .with_context(|| "建立读快照失败")?;
+        Ok(snapshot)
     }
 
-    /// 创建只读连接
-    pub fn connect(&self) -> AppResult<Connection> {
-        Connection::open_with_flags(&self.path, OpenFlags::SQLITE_OPEN_READ_ONLY)
-            .with_context(|| format!("无法打开数据库: {}", self.path.display()))
+    /// 在一个 IMMEDIATE 写事务中执行 `f`，成功时提交；`f` 返回错误时回滚
+    ///
+    /// 仅用于显式的写操作（如 `ccstart use`、`add`、`edit`），读取仍走共享只读连接
+    pub fn write<T>(&self, f: impl FnOnce(&Transaction<'_>) -> AppResult<T>) -> AppResult<T> {
+        let mut conn = self.connect_writable()?;
+        self.auto_backup()?;
+        let tx = conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .with_context(|| "开启写事务失败（cc-switch 可能正在写入，请稍后重试）")?;
+        let result = f(&tx)?;
+        tx.commit().with_context(|| "提交事务失败")?;
+        Ok(result)
+    }
+
+    /// 使用在线备份 API 将数据库复制到 `dest`（先写临时文件，完成后再改名）
+    pub fn backup_to(&self, dest: &Path) -> AppResult<()> {
+        let partial = dest.with_extension("partial");
+        let result = with_busy_retry(|| {
+            self.connect()?
+                .backup(DatabaseName::Main, &partial, None)
+                .map_err(anyhow::Error::from)
+        })
+        .and_then(|_| fs::rename(&partial, dest).map_err(anyhow::Error::from));
+        if result.is_err() {
+            let _ = fs::remove_file(&partial);
+        }
+        result.with_context(|| format!("备份数据库失败: {}", dest.display()))
+    }
+
+    /// 用快照整体替换数据库内容（在线备份 API 反向复制）
+    ///
+    /// 从已打开的快照连接复制：快照文件之后即使被删除，也不会把空库覆盖到数据库上
+    pub fn restore_from(&self, snapshot: &Database) -> AppResult<()> {
+        let source = snapshot.path();
+        if !source.is_file() {
+            anyhow::bail!("快照不存在: {}", source.display());
+        }
+        let src = snapshot.connect()?;
+        let check: String = src
+            .query_row("PRAGMA quick_check", [], |row| row.get(0))
+            .with_context(|| format!("快照无法读取: {}", source.display()))?;
+        if check != "ok" {
+            anyhow::bail!("快照已损坏: {}（{}）", source.display(), check);
+        }
+        snapshot
+            .capabilities()
+            .with_context(|| format!("快照不是可用的 cc-switch 数据库: {}", source.display()))?;
+
+        let mut conn = self.connect_writable()?;
+        let backup = Backup::new(src, &mut conn).with_context(|| format!("从快照恢复失败: {}", source.display()))?;
+        let mut waited = 0;
+        loop {
+            let step = backup
+                .step(256)
+                .with_context(|| format!("从快照恢复失败: {}", source.display()))?;
+            match step {
+                StepResult::Done => return Ok(()),
+                StepResult::Busy | StepResult::Locked if waited < RESTORE_RETRIES => {
+                    waited += 1;
+                    thread::sleep(RESTORE_PAUSE);
+                }
+                StepResult::Busy | StepResult::Locked => {
+                    anyhow::bail!("从快照恢复失败: 数据库被占用（cc-switch 可能正在写入），请稍后重试")
+                }
+                _ => waited = 0,
+            }
+        }
+    }
+
+    /// 本进程第一次写入前创建快照
+    fn auto_backup(&self) -> AppResult<()> {
+        let Some(store) = &self.backups else {
+            return Ok(());
+        };
+        if self.backed_up.get() {
+            return Ok(());
+        }
+        let path = store.create(self, "auto").with_context(|| {
+            "写入前备份数据库失败\n提示: 可在配置文件的 [backup] 中设置 auto = false 关闭自动备份"
+        })?;
+        self.backed_up.set(true);
+        eprintln!("[INFO] 已备份数据库: {}", path.display());
+        Ok(())
+    }
+
+    /// immutable 模式下拒绝写入
+    pub fn check_writable(&self) -> AppResult<()> {
+        if self.mode == DbMode::Immutable {
+            anyhow::bail!("数据库以 immutable 模式打开，不允许写入\n提示: 去掉 --db-mode immutable 后重试");
+        }
+        Ok(())
+    }
+
+    /// 创建可写连接
+    fn connect_writable(&self) -> AppResult<Connection> {
+        self.check_writable()?;
+        let conn = Connection::open_with_flags(&self.path, OpenFlags::SQLITE_OPEN_READ_WRITE)
+            .with_context(|| format!("无法以读写模式打开数据库: {}", self.path.display()))?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        Ok(conn)
+    }
+
+    fn shared(&self) -> AppResult<&Shared> {
+        if let Some(shared) = self.shared.get() {
+            return Ok(shared);
+        }
+        let shared = with_busy_retry(|| self.open_shared())?;
+        // 动态补全时不输出，避免干扰 shell
+        if shared.caps.is_newer_than_known() && std::env::var_os("COMPLETE").is_none() {
+            eprintln!(
+                "[WARN] cc-switch 数据库 schema v{} 高于 ccstart 已知的 v{}，按探测到的结构使用；如遇问题请升级 ccstart",
+                shared.caps.version,
+                schema::MAX_SCHEMA_VERSION
+            );
+        }
+        Ok(self.shared.get_or_init(|| shared))
+    }
+
+    /// 打开连接并探测 schema
+    fn open_shared(&self) -> AppResult<Shared> {
+        let conn = if self.use_immutable() {
+            let uri = format!("file:{}?immutable=1", encode_uri_path(&self.path));
+            Connection::open_with_flags(
+                uri,
+                OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_URI,
+            )
+        } else {
+            Connection::open_with_flags(&self.path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        }
+        .with_context(|| format!("无法打开数据库: {}", self.path.display()))?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+
+        let caps = Capabilities::detect(&conn)?;
+        Ok(Shared { conn, caps })
+    }
+
+    /// WAL 数据库在只读目录中无法创建 -shm，此时只能以 immutable 方式读取
+    fn use_immutable(&self) -> bool {
+        match self.mode {
+            DbMode::Immutable => true,
+            DbMode::Auto => {
+                let shm = self.path.with_file_name(format!(
+                    "{}-shm",
+                    self.path.file_name().unwrap_or_default().to_string_lossy()
+                ));
+                let dir_readonly = self
+                    .path
+                    .parent()
+                    .and_then(|d| fs::metadata(d).ok())
+                    .is_some_and(|m| m.permissions().readonly());
+                is_wal(&self.path) && !shm.exists() && dir_readonly
+            }
+        }
     }
 
     /// 获取 Provider DAO
     pub fn providers(&self) -> ProviderDao<'_> {
         ProviderDao::new(self)
     }
+
+    /// 获取端点 DAO
+    pub fn endpoints(&self) -> EndpointDao<'_> {
+        EndpointDao::new(self)
+    }
+
+    /// 获取 MCP 服务器 DAO
+    pub fn mcp_servers(&self) -> McpDao<'_> {
+        McpDao::new(self)
+    }
+
+    /// 获取提示词 DAO
+    pub fn prompts(&self) -> PromptDao<'_> {
+        PromptDao::new(self)
+    }
+
+    /// 获取技能 DAO
+    pub fn skills(&self) -> SkillDao<'_> {
+        SkillDao::new(self)
+    }
+
+    /// 获取通用设置 DAO
+    pub fn settings(&self) -> SettingsDao<'_> {
+        SettingsDao::new(self)
+    }
+}
+
+/// 读快照 guard，drop 时结束读事务
+pub struct Snapshot<'a> {
+    conn: &'a Connection,
+    active: bool,
+}
+
+impl Drop for Snapshot<'_> {
+    fn drop(&mut self) {
+        if self.active {
+            let _ = self.conn.execute_batch("COMMIT");
+        }
+    }
+}
+
+/// cc-switch 持有写锁时重试（每次语句内部已等待 BUSY_TIMEOUT）
+fn with_busy_retry<T>(mut f: impl FnMut() -> AppResult<T>) -> AppResult<T> {
+    let mut attempt = 0;
+    loop {
+        match f() {
+            Err(e) if attempt < BUSY_RETRIES && is_busy(&e) => {
+                attempt += 1;
+                thread::sleep(Duration::from_millis(200 * u64::from(attempt)));
+            }
+            other => return other,
+        }
+    }
+}
+
+fn is_busy(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<rusqlite::Error>().and_then(|e| e.sqlite_error_code()),
+            Some(ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked)
+        )
+    })
+}
+
+/// 读取文件头判断是否为 WAL 模式（偏移 18/19 的读写版本号为 2）
+fn is_wal(path: &Path) -> bool {
+    use std::io::Read;
+
+    let mut header = [0u8; 20];
+    fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut header))
+        .map(|_| header[18] == 2 && header[19] == 2)
+        .unwrap_or(false)
+}
+
+/// URI 文件名中需要转义的字符
+fn encode_uri_path(path: &Path) -> String {
+    const URI: &percent_encoding::AsciiSet = &percent_encoding::CONTROLS
+        .add(b'?')
+        .add(b'#')
+        .add(b'%')
+        .add(b' ');
+    let raw = path.to_string_lossy().replace('\\', "/");
+    percent_encoding::utf8_percent_encode(&raw, URI).to_string()
 }
@@ -0,0 +1,83 @@
+use crate::error::AppResult;
+use anyhow::Context;
+use rusqlite::{params, Row};
+
+use super::{AppType, Database};
+
+/// 提示词数据模型（对应 prompts 表）
+#[derive(Debug, Clone)]
+pub struct Prompt {
+    #[allow(dead_code)]
+    pub id: String,
+    pub name: String,
+    pub content: String,
+    pub description: Option<String>,
+    pub enabled: bool,
+}
+
+impl Prompt {
+    fn from_row(row: &Row<'_>) -> rusqlite::Result<Self> {
+        Ok(Prompt {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            content: row.get(2)?,
+            description: row.get(3)?,
+            enabled: row.get(4)?,
+        })
+    }
+}
+
+/// 提示词数据访问对象
+pub struct PromptDao<'a> {
+    db: &'a Database,
+}
+
+impl<'a> PromptDao<'a> {
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// 获取指定应用的所有提示词
+    pub fn list_all(&self, app: AppType) -> AppResult<Vec<Prompt>> {
+        if !self.db.capabilities()?.table_available("prompts")? {
+            return Ok(Vec::new());
+        }
+        let conn = self.db.connect()?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, name, content, description, enabled
+                 FROM prompts
+                 WHERE app_type = ?1
+                 ORDER BY created_at, name",
+            )
+            .with_context(|| "准备查询语句失败")?;
+
+        let iter = stmt
+            .query_map(params![app.as_str()], Prompt::from_row)
+            .with_context(|| "执行查询失败")?;
+
+        iter.collect::<Result<Vec<_>, _>>()
+            .with_context(|| "读取查询结果失败")
+    }
+
+    /// 根据名称获取提示词
+    pub fn get_by_name(&self, app: AppType, name: &str) -> AppResult<Option<Prompt>> {
+        if !self.db.capabilities()?.table_available("prompts")? {
+            return Ok(None);
+        }
+        let conn = self.db.connect()?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, name, content, description, enabled
+                 FROM prompts
+                 WHERE app_type = ?1 AND name = ?2",
+            )
+            .with_context(|| "准备查询语句失败")?;
+
+        match stmt.query_row(params![app.as_str(), name], Prompt::from_row) {
+            Ok(p) => Ok(Some(p)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e).with_context(|| format!("查询提示词 '{}' 失败", name)),
+        }
+    }
+}
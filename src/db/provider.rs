@@ -1,17 +1,139 @@
-use crate::error::AppResult;
+use crate::error::{AppResult, ConfigError, JsonError};
 use anyhow::Context;
-use rusqlite::params;
+use rusqlite::{params, OptionalExtension, Row, Transaction};
 use serde_json::Value;
+use std::borrow::Cow;
 
-use super::Database;
+use super::{AppType, Database};
+
+/// 查询 provider 时附带的列：同一应用下是否存在同名 provider
+const DUPLICATE_COLUMN: &str = "(SELECT COUNT(*) FROM providers AS d
+    WHERE d.app_type = providers.app_type AND d.name = providers.name) > 1";
 
 /// Provider 数据模型
 #[derive(Debug, Clone)]
 pub struct Provider {
-    #[allow(dead_code)]
     pub id: String,
+    pub app_type: AppType,
+    pub name: String,
+    pub settings_config: Value,
+    /// settings_config 无法解析时的错误（此时 settings_config 为 null，不可用于启动）
+    pub load_error: Option<JsonError>,
+    /// 同一应用下存在同名 provider（主键为 id，名称不唯一）
+    pub duplicate: bool,
+    pub category: Option<String>,
+    pub website_url: Option<String>,
+    pub notes: Option<String>,
+    /// cc-switch 界面中的图标颜色（如 `#ff6600`）
+    pub icon_color: Option<String>,
+    /// cc-switch 中标记为当前的配置
+    pub is_current: bool,
+}
+
+impl Provider {
+    fn from_row(app: AppType, row: &Row<'_>) -> rusqlite::Result<Self> {
+        let settings_str: String = row.get(2)?;
+        let (settings_config, load_error) = match serde_json::from_str(&settings_str) {
+            Ok(value) => (value, None),
+            Err(e) => (Value::Null, Some(JsonError::from_serde(&e))),
+        };
+        Ok(Provider {
+            id: row.get(0)?,
+            app_type: app,
+            name: row.get(1)?,
+            settings_config,
+            load_error,
+            duplicate: row.get(3)?,
+            category: row.get(4)?,
+            website_url: row.get(5)?,
+            notes: row.get(6)?,
+            icon_color: row.get(7)?,
+            is_current: row.get::<_, Option<bool>>(8)?.unwrap_or(false),
+        })
+    }
+
+    /// 区分同名配置的名称：唯一时为 `name`，重名时为 `name#id`（也用作缓存文件名）
+    pub fn qualified_name(&self) -> Cow<'_, str> {
+        if self.duplicate {
+            Cow::Owned(format!("{}#{}", self.name, self.id))
+        } else {
+            Cow::Borrowed(&self.name)
+        }
+    }
+
+    /// 校验 settings_config 可用；损坏的配置返回 `ConfigError::InvalidStructure`
+    pub fn check(&self) -> AppResult<()> {
+        match &self.load_error {
+            Some(err) => Err(ConfigError::InvalidStructure(format!(
+                "{} 配置 '{}' 的 settings_config 无法解析（{}）",
+                self.app_type.label(),
+                self.name,
+                err
+            ))
+            .into()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// 新建 provider 时的输入（id、排序与创建时间由 DAO 生成）
+#[derive(Debug, Clone, Default)]
+pub struct NewProvider {
     pub name: String,
     pub settings_config: Value,
+    pub category: Option<String>,
+    pub website_url: Option<String>,
+    pub notes: Option<String>,
+    pub icon_color: Option<String>,
+}
+
+/// 导入操作中的一项（`overwrite` 为 true 时覆盖 id 对应的已有 provider）
+#[derive(Debug, Clone)]
+pub struct ImportItem {
+    pub app: AppType,
+    pub id: String,
+    pub provider: NewProvider,
+    /// None 时保留现有端点（导入文件中没有端点信息）
+    pub endpoints: Option<Vec<String>>,
+    pub overwrite: bool,
+}
+
+/// 写入前校验 settings_config 的结构，避免写入 cc-switch 无法使用的配置
+pub fn validate_settings(app: AppType, settings: &Value) -> AppResult<()> {
+    let invalid = |msg: &str| -> AppResult<()> {
+        Err(ConfigError::InvalidStructure(format!("{} settings_config {}", app.label(), msg)).into())
+    };
+    let Some(obj) = settings.as_object() else {
+        return invalid("必须是 JSON 对象");
+    };
+    let expect_object = |key: &str| obj.get(key).is_none_or(Value::is_object);
+    match app {
+        AppType::Claude => {
+            if !expect_object("env") {
+                return invalid("中的 env 必须是对象");
+            }
+        }
+        AppType::Codex => {
+            if !expect_object("auth") {
+                return invalid("中的 auth 必须是对象");
+            }
+            match obj.get("config") {
+                None => {}
+                Some(Value::String(config)) => {
+                    if let Err(e) = config.parse::<toml::Table>() {
+                        return invalid(&format!("中的 config 不是合法的 TOML: {}", e.message()));
+                    }
+                }
+                Some(_) => return invalid("中的 config 必须是 TOML 字符串"),
+            }
+        }
+        AppType::Gemini => {
+            if !expect_object("env") || !expect_object("config") {
+                return invalid("中的 env 与 config 必须是对象");
+            }
+        }
+    }
+    Ok(())
 }
 
 /// Provider 数据访问对象
@@ -24,77 +146,455 @@ impl<'a> ProviderDao<'a> {
         Self { db }
     }
 
-    /// 获取所有 Claude provider
-    pub fn list_all(&self) -> AppResult<Vec<Provider>> {
+    /// Provider::from_row 对应的 SELECT 列表；旧版 schema 缺少的可选列以 NULL 代替
+    fn select_columns(&self) -> AppResult<String> {
+        let caps = self.db.capabilities()?;
+        Ok(format!(
+            "id, name, settings_config, {}, {}, {}, {}, {}, {}",
+            DUPLICATE_COLUMN,
+            caps.provider_column("category"),
+            caps.provider_column("website_url"),
+            caps.provider_column("notes"),
+            caps.provider_column("icon_color"),
+            caps.provider_column("is_current"),
+        ))
+    }
+
+    /// 获取指定应用的所有 provider
+    pub fn list_all(&self, app: AppType) -> AppResult<Vec<Provider>> {
         let conn = self.db.connect()?;
-        let mut stmt = conn
-            .prepare(
-                "SELECT id, name, settings_config
-                 FROM providers
-                 WHERE app_type = 'claude'
-                 ORDER BY sort_index, name",
-            )
-            .with_context(|| "准备查询语句失败")?;
+        let sql = format!(
+            "SELECT {}
+             FROM providers
+             WHERE app_type = ?1
+             {}",
+            self.select_columns()?,
+            self.db.capabilities()?.provider_order()
+        );
+        let mut stmt = conn.prepare(&sql).with_context(|| "准备查询语句失败")?;
 
         let iter = stmt
-            .query_map([], |row| {
-                let settings_str: String = row.get(2)?;
-                Ok(Provider {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    settings_config: serde_json::from_str(&settings_str).unwrap_or(Value::Null),
-                })
-            })
+            .query_map(params![app.as_str()], |row| Provider::from_row(app, row))
             .with_context(|| "执行查询失败")?;
 
         iter.collect::<Result<Vec<_>, _>>()
             .with_context(|| "读取查询结果失败")
     }
 
-    /// 根据名称获取 Provider
-    pub fn get_by_name(&self, name: &str) -> AppResult<Option<Provider>> {
+    /// 根据名称获取 Provider；存在同名配置时返回 `ConfigError::DuplicateName`
+    pub fn get_by_name(&self, app: AppType, name: &str) -> AppResult<Option<Provider>> {
+        let conn = self.db.connect()?;
+        let sql = format!(
+            "SELECT {}
+             FROM providers
+             WHERE app_type = ?1 AND name = ?2",
+            self.select_columns()?
+        );
+        let mut stmt = conn.prepare(&sql).with_context(|| "准备查询语句失败")?;
+
+        let mut found: Vec<Provider> = stmt
+            .query_map(params![app.as_str(), name], |row| Provider::from_row(app, row))
+            .and_then(|rows| rows.collect())
+            .with_context(|| format!("查询 provider '{}' 失败", name))?;
+
+        match found.len() {
+            0 => Ok(None),
+            1 => Ok(found.pop()),
+            _ => {
+                let ids: Vec<&str> = found.iter().map(|p| p.id.as_str()).collect();
+                Err(ConfigError::DuplicateName(format!(
+                    "{} 配置 '{}' 存在 {} 个（id: {}）",
+                    app.label(),
+                    name,
+                    found.len(),
+                    ids.join(", ")
+                ))
+                .into())
+            }
+        }
+    }
+
+    /// 根据 id 获取 Provider
+    pub fn get_by_id(&self, app: AppType, id: &str) -> AppResult<Option<Provider>> {
         let conn = self.db.connect()?;
-        let mut stmt = conn
-            .prepare(
-                "SELECT id, name, settings_config
-                 FROM providers
-                 WHERE app_type = 'claude' AND name = ?1",
-            )
-            .with_context(|| "准备查询语句失败")?;
-
-        let result = stmt.query_row(params![name], |row| {
-            let settings_str: String = row.get(2)?;
-            Ok(Provider {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                settings_config: serde_json::from_str(&settings_str).unwrap_or(Value::Null),
-            })
-        });
-
-        match result {
+        let sql = format!(
+            "SELECT {}
+             FROM providers
+             WHERE app_type = ?1 AND id = ?2",
+            self.select_columns()?
+        );
+        conn.query_row(&sql, params![app.as_str(), id], |row| Provider::from_row(app, row))
+            .optional()
+            .with_context(|| format!("查询 provider '{}' 失败", id))
+    }
+
+    /// 获取 cc-switch 中标记为当前（is_current = 1）的 provider
+    pub fn get_current(&self, app: AppType) -> AppResult<Option<Provider>> {
+        self.require_is_current()?;
+        let conn = self.db.connect()?;
+        let sql = format!(
+            "SELECT {}
+             FROM providers
+             WHERE app_type = ?1 AND is_current = 1
+             {}
+             LIMIT 1",
+            self.select_columns()?,
+            self.db.capabilities()?.provider_order()
+        );
+        let mut stmt = conn.prepare(&sql).with_context(|| "准备查询语句失败")?;
+
+        match stmt.query_row(params![app.as_str()], |row| Provider::from_row(app, row)) {
             Ok(p) => Ok(Some(p)),
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(e).with_context(|| format!("查询 provider '{}' 失败", name)),
+            Err(e) => Err(e).with_context(|| "查询当前 provider 失败"),
         }
     }
 
-    /// 获取所有 Claude provider 名称列表
-    pub fn list_names(&self) -> AppResult<Vec<String>> {
+    /// 将指定 provider 设为当前配置（同一事务内清除同应用下其它 provider 的标记）
+    pub fn set_current(&self, app: AppType, id: &str) -> AppResult<()> {
+        self.require_is_current()?;
+        self.db.write(|tx| {
+            let updated = tx
+                .execute(
+                    "UPDATE providers SET is_current = (id = ?1) WHERE app_type = ?2",
+                    params![id, app.as_str()],
+                )
+                .with_context(|| "更新当前 provider 失败")?;
+            let current: i64 = tx
+                .query_row(
+                    "SELECT COUNT(*) FROM providers WHERE app_type = ?1 AND is_current = 1",
+                    params![app.as_str()],
+                    |row| row.get(0),
+                )
+                .with_context(|| "校验当前 provider 失败")?;
+            if updated == 0 || current != 1 {
+                anyhow::bail!("未找到 id 为 '{}' 的 {} provider", id, app.label());
+            }
+            Ok(())
+        })
+    }
+
+    /// 获取指定应用的所有 provider 名称列表（同名配置以 `name#id` 区分）
+    pub fn list_names(&self, app: AppType) -> AppResult<Vec<String>> {
         let conn = self.db.connect()?;
-        let mut stmt = conn
-            .prepare(
-                "SELECT name FROM providers
-                 WHERE app_type = 'claude'
-                 ORDER BY sort_index, name",
-            )
-            .with_context(|| "准备查询语句失败")?;
+        let sql = format!(
+            "SELECT CASE WHEN {} THEN name || '#' || id ELSE name END
+             FROM providers
+             WHERE app_type = ?1
+             {}",
+            DUPLICATE_COLUMN,
+            self.db.capabilities()?.provider_order()
+        );
+        let mut stmt = conn.prepare(&sql).with_context(|| "准备查询语句失败")?;
 
         let names: Vec<String> = stmt
-            .query_map([], |row| row.get(0))
+            .query_map(params![app.as_str()], |row| row.get(0))
             .with_context(|| "执行查询失败")?
             .collect::<Result<_, _>>()
             .with_context(|| "读取查询结果失败")?;
 
         Ok(names)
     }
+
+    /// 新增 provider，返回生成的 id；同一应用下名称不可重复
+    pub fn insert(&self, app: AppType, new: &NewProvider) -> AppResult<String> {
+        validate_settings(app, &new.settings_config)?;
+        let caps = self.db.capabilities()?;
+        let id = uuid::Uuid::new_v4().to_string();
+        self.db.write(|tx| {
+            ensure_unique_name(tx, app, &new.name, None)?;
+            insert_row(tx, caps, app, &id, new)
+        })?;
+        Ok(id)
+    }
+
+    /// 在同一事务中执行导入：新增或覆盖 provider，导入文件带有端点时替换其端点
+    pub fn import(&self, items: &[ImportItem]) -> AppResult<()> {
+        for item in items {
+            validate_settings(item.app, &item.provider.settings_config)?;
+        }
+        let caps = self.db.capabilities()?;
+        self.db.write(|tx| {
+            for item in items {
+                let new = &item.provider;
+                if item.overwrite {
+                    let mut sets = vec!["name = ?".to_string(), "settings_config = ?".to_string()];
+                    let mut values: Vec<Box<dyn rusqlite::ToSql>> = vec![
+                        Box::new(new.name.clone()),
+                        Box::new(serde_json::to_string(&new.settings_config)?),
+                    ];
+                    for (column, value) in optional_values(new) {
+                        if caps.has_provider_column(column) {
+                            sets.push(format!("{} = ?", column));
+                            values.push(value);
+                        }
+                    }
+                    values.push(Box::new(item.app.as_str()));
+                    values.push(Box::new(item.id.clone()));
+                    let sql = format!(
+                        "UPDATE providers SET {} WHERE app_type = ? AND id = ?",
+                        sets.join(", ")
+                    );
+                    let updated = tx
+                        .execute(&sql, rusqlite::params_from_iter(values.iter()))
+                        .with_context(|| format!("覆盖 provider '{}' 失败", new.name))?;
+                    require_updated(updated, item.app, &item.id)?;
+                } else {
+                    ensure_unique_name(tx, item.app, &new.name, None)?;
+                    insert_row(tx, caps, item.app, &item.id, new)?;
+                }
+
+                if let Some(endpoints) = &item.endpoints
+                    && caps.table_available("provider_endpoints")?
+                {
+                    tx.execute(
+                        "DELETE FROM provider_endpoints WHERE app_type = ?1 AND provider_id = ?2",
+                        params![item.app.as_str(), item.id],
+                    )
+                    .with_context(|| "清除 provider 端点失败")?;
+                    for url in endpoints {
+                        tx.execute(
+                            "INSERT INTO provider_endpoints (provider_id, app_type, url, added_at)
+                             VALUES (?1, ?2, ?3, ?4)",
+                            params![item.id, item.app.as_str(), url, now_millis()],
+                        )
+                        .with_context(|| "写入 provider 端点失败")?;
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// 在同一写事务中读取并修改 provider 的 settings_config：`edit` 收到数据库中的最新内容，
+    /// 返回 None 表示不修改。读取与写入之间 cc-switch 无法写入，不会覆盖其修改；返回实际写入的内容
+    pub fn modify_settings(
+        &self,
+        app: AppType,
+        ids: &[String],
+        mut edit: impl FnMut(&str, &Value) -> AppResult<Option<Value>>,
+    ) -> AppResult<Vec<(String, Value)>> {
+        self.db.write(|tx| {
+            let mut updates = Vec::new();
+            for id in ids {
+                let current: Option<String> = tx
+                    .query_row(
+                        "SELECT settings_config FROM providers WHERE app_type = ?1 AND id = ?2",
+                        params![app.as_str(), id],
+                        |row| row.get(0),
+                    )
+                    .optional()
+                    .with_context(|| format!("查询 provider '{}' 失败", id))?;
+                let Some(current) = current else {
+                    return require_updated(0, app, id).map(|_| Vec::new());
+                };
+                let current: Value = serde_json::from_str(&current).map_err(|e| {
+                    ConfigError::InvalidStructure(format!(
+                        "{} 配置 '{}' 的 settings_config 无法解析（{}）",
+                        app.label(),
+                        id,
+                        JsonError::from_serde(&e)
+                    ))
+                })?;
+                let Some(settings) = edit(id, &current)? else {
+                    continue;
+                };
+                validate_settings(app, &settings)?;
+                tx.execute(
+                    "UPDATE providers SET settings_config = ?1 WHERE app_type = ?2 AND id = ?3",
+                    params![serde_json::to_string(&settings)?, app.as_str(), id],
+                )
+                .with_context(|| "更新 settings_config 失败")?;
+                updates.push((id.clone(), settings));
+            }
+            Ok(updates)
+        })
+    }
+
+    /// 重命名 provider；新名称不可与同应用下其它配置重复
+    pub fn rename(&self, app: AppType, id: &str, new_name: &str) -> AppResult<()> {
+        self.db.write(|tx| {
+            ensure_unique_name(tx, app, new_name, Some(id))?;
+            let updated = tx
+                .execute(
+                    "UPDATE providers SET name = ?1 WHERE app_type = ?2 AND id = ?3",
+                    params![new_name, app.as_str(), id],
+                )
+                .with_context(|| "重命名 provider 失败")?;
+            require_updated(updated, app, id)
+        })
+    }
+
+    /// 复制 provider 的所有列（新 id、新名称，不继承当前标记）及其端点，返回新 id
+    pub fn clone_provider(&self, app: AppType, id: &str, new_name: &str) -> AppResult<String> {
+        let caps = self.db.capabilities()?;
+        let new_id = uuid::Uuid::new_v4().to_string();
+        let copied: Vec<&str> = caps
+            .provider_column_names()
+            .filter(|c| !matches!(*c, "id" | "name" | "is_current" | "created_at"))
+            .collect();
+        let mut columns = vec!["id", "name"];
+        let mut selects = vec!["?1", "?2"];
+        for extra in ["is_current", "created_at"] {
+            if caps.has_provider_column(extra) {
+                columns.push(extra);
+                selects.push(if extra == "is_current" { "0" } else { "?5" });
+            }
+        }
+        columns.extend(&copied);
+        selects.extend(&copied);
+        let sql = format!(
+            "INSERT INTO providers ({}) SELECT {} FROM providers WHERE app_type = ?3 AND id = ?4",
+            columns.join(", "),
+            selects.join(", ")
+        );
+
+        self.db.write(|tx| {
+            ensure_unique_name(tx, app, new_name, None)?;
+            let mut stmt = tx.prepare(&sql).with_context(|| "准备复制语句失败")?;
+            let inserted = if caps.has_provider_column("created_at") {
+                stmt.execute(params![new_id, new_name, app.as_str(), id, now_millis()])
+            } else {
+                stmt.execute(params![new_id, new_name, app.as_str(), id])
+            }
+            .with_context(|| "复制 provider 失败")?;
+            require_updated(inserted, app, id)?;
+            if caps.table_available("provider_endpoints")? {
+                tx.execute(
+                    "INSERT INTO provider_endpoints (provider_id, app_type, url, added_at)
+                     SELECT ?1, app_type, url, added_at FROM provider_endpoints
+                     WHERE app_type = ?2 AND provider_id = ?3",
+                    params![new_id, app.as_str(), id],
+                )
+                .with_context(|| "复制 provider 端点失败")?;
+            }
+            Ok(())
+        })?;
+        Ok(new_id)
+    }
+
+    /// 删除 provider 及其记录的端点
+    pub fn delete(&self, app: AppType, id: &str) -> AppResult<()> {
+        let has_endpoints = self.db.capabilities()?.table_available("provider_endpoints")?;
+        self.db.write(|tx| {
+            if has_endpoints {
+                tx.execute(
+                    "DELETE FROM provider_endpoints WHERE app_type = ?1 AND provider_id = ?2",
+                    params![app.as_str(), id],
+                )
+                .with_context(|| "删除 provider 端点失败")?;
+            }
+            let deleted = tx
+                .execute(
+                    "DELETE FROM providers WHERE app_type = ?1 AND id = ?2",
+                    params![app.as_str(), id],
+                )
+                .with_context(|| "删除 provider 失败")?;
+            require_updated(deleted, app, id)
+        })
+    }
+
+    /// 旧版 cc-switch 没有 is_current 列
+    fn require_is_current(&self) -> AppResult<()> {
+        let caps = self.db.capabilities()?;
+        if !caps.has_provider_column("is_current") {
+            anyhow::bail!(
+                "当前 cc-switch 数据库（schema v{}）不支持当前配置标记（缺少 providers.is_current）\n提示: 请升级 cc-switch",
+                caps.version
+            );
+        }
+        Ok(())
+    }
+}
+
+/// 新增一行 provider；新配置排在末尾
+fn insert_row(
+    tx: &Transaction<'_>,
+    caps: &super::Capabilities,
+    app: AppType,
+    id: &str,
+    new: &NewProvider,
+) -> AppResult<()> {
+    let mut columns = vec!["id", "app_type", "name", "settings_config"];
+    let mut values: Vec<Box<dyn rusqlite::ToSql>> = vec![
+        Box::new(id.to_string()),
+        Box::new(app.as_str()),
+        Box::new(new.name.clone()),
+        Box::new(serde_json::to_string(&new.settings_config)?),
+    ];
+    let created_at: (&str, Box<dyn rusqlite::ToSql>) = ("created_at", Box::new(now_millis()));
+    for (column, value) in optional_values(new).into_iter().chain([created_at]) {
+        if caps.has_provider_column(column) {
+            columns.push(column);
+            values.push(value);
+        }
+    }
+
+    let placeholders = vec!["?"; columns.len()].join(", ");
+    let sql = if caps.has_provider_column("sort_index") {
+        format!(
+            "INSERT INTO providers ({}, sort_index) SELECT {}, COALESCE(MAX(sort_index), -1) + 1
+             FROM providers WHERE app_type = '{}'",
+            columns.join(", "),
+            placeholders,
+            app.as_str()
+        )
+    } else {
+        format!("INSERT INTO providers ({}) VALUES ({})", columns.join(", "), placeholders)
+    };
+    tx.execute(&sql, rusqlite::params_from_iter(values.iter()))
+        .with_context(|| format!("新增 provider '{}' 失败", new.name))?;
+    Ok(())
+}
+
+/// 可选列及其取值（列不存在时由调用方跳过）
+fn optional_values(new: &NewProvider) -> [(&'static str, Box<dyn rusqlite::ToSql>); 4] {
+    [
+        ("category", Box::new(new.category.clone())),
+        ("website_url", Box::new(new.website_url.clone())),
+        ("notes", Box::new(new.notes.clone())),
+        ("icon_color", Box::new(new.icon_color.clone())),
+    ]
+}
+
+/// 同一应用下的名称唯一性检查（`except_id` 为正在重命名的 provider 自身）
+fn ensure_unique_name(
+    tx: &Transaction<'_>,
+    app: AppType,
+    name: &str,
+    except_id: Option<&str>,
+) -> AppResult<()> {
+    let existing: Option<String> = tx
+        .query_row(
+            "SELECT id FROM providers WHERE app_type = ?1 AND name = ?2 AND id IS NOT ?3 LIMIT 1",
+            params![app.as_str(), name, except_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .with_context(|| "检查名称是否重复失败")?;
+    match existing {
+        Some(id) => Err(ConfigError::DuplicateName(format!(
+            "{} 配置 '{}' 已存在（id: {}）",
+            app.label(),
+            name,
+            id
+        ))
+        .into()),
+        None => Ok(()),
+    }
+}
+
+fn require_updated(count: usize, app: AppType, id: &str) -> AppResult<()> {
+    if count == 0 {
+        anyhow::bail!("未找到 id 为 '{}' 的 {} provider", id, app.label());
+    }
+    Ok(())
+}
+
+/// 当前时间（Unix 毫秒），与 cc-switch 的 created_at 一致
+fn now_millis() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
 }
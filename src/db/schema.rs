@@ -0,0 +1,162 @@
+use crate::error::AppResult;
+use anyhow::Context;
+use rusqlite::Connection;
+use std::collections::{HashMap, HashSet};
+
+/// ccstart 已知的最高 cc-switch schema 版本（PRAGMA user_version）；更高版本在必需列齐全时仍按能力降级使用。
+/// cc-switch 早期的数据库不设置 user_version（为 0），不按版本号拒绝，是否可用只看下面的结构校验
+pub const MAX_SCHEMA_VERSION: i32 = 1;
+
+/// providers 表中 ccstart 必需的列
+const REQUIRED_PROVIDER_COLUMNS: &[&str] = &["id", "app_type", "name", "settings_config"];
+
+/// 可选表及 ccstart 查询用到的列：表不存在时相应功能为空，表存在但缺少列时使用该功能报错
+const OPTIONAL_TABLES: &[(&str, &[&str])] = &[
+    ("provider_endpoints", &["id", "provider_id", "app_type", "url", "added_at"]),
+    ("mcp_servers", &["id", "name", "server_config", "description", "enabled_claude"]),
+    ("prompts", &["id", "app_type", "name", "content", "description", "enabled", "created_at"]),
+    ("skills", &["key", "installed"]),
+    ("skill_repos", &["owner", "name", "branch", "enabled"]),
+    ("settings", &["key", "value"]),
+];
+
+/// 打开数据库时探测到的 schema 能力
+#[derive(Debug, Clone)]
+pub struct Capabilities {
+    /// PRAGMA user_version
+    pub version: i32,
+    tables: HashSet<String>,
+    provider_columns: HashSet<String>,
+    /// 存在但缺少所需列的可选表
+    incompatible: HashMap<&'static str, Vec<&'static str>>,
+}
+
+impl Capabilities {
+    /// 读取 user_version、表清单与 providers 列信息，并校验必需结构
+    pub fn detect(conn: &Connection) -> AppResult<Self> {
+        let version: i32 = conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .with_context(|| "读取 schema 版本失败")?;
+
+        let tables = {
+            let mut stmt = conn
+                .prepare("SELECT name FROM sqlite_master WHERE type = 'table'")
+                .with_context(|| "读取表清单失败")?;
+            stmt.query_map([], |row| row.get::<_, String>(0))?
+                .collect::<Result<HashSet<_>, _>>()
+                .with_context(|| "读取表清单失败")?
+        };
+
+        let provider_columns = table_columns(conn, "providers")?;
+
+        let mut incompatible = HashMap::new();
+        for (table, required) in OPTIONAL_TABLES {
+            if !tables.contains(*table) {
+                continue;
+            }
+            let columns = table_columns(conn, table)?;
+            let missing: Vec<&str> = required.iter().copied().filter(|c| !columns.contains(*c)).collect();
+            if !missing.is_empty() {
+                incompatible.insert(*table, missing);
+            }
+        }
+
+        let caps = Self {
+            version,
+            tables,
+            provider_columns,
+            incompatible,
+        };
+        caps.validate()?;
+        Ok(caps)
+    }
+
+    /// 版本高于 ccstart 已知的最高版本（必需结构齐全时仍可使用，但应提示升级）
+    pub fn is_newer_than_known(&self) -> bool {
+        self.version > MAX_SCHEMA_VERSION
+    }
+
+    /// 缺少必需结构时给出包含版本信息的错误
+    fn validate(&self) -> AppResult<()> {
+        let missing: Vec<String> = if self.has_table("providers") {
+            REQUIRED_PROVIDER_COLUMNS
+                .iter()
+                .filter(|c| !self.has_provider_column(c))
+                .map(|c| format!("providers.{}", c))
+                .collect()
+        } else {
+            vec!["providers".to_string()]
+        };
+
+        if missing.is_empty() {
+            return Ok(());
+        }
+        Err(self.version_error(&missing.join(", ")))
+    }
+
+    fn version_error(&self, missing: &str) -> anyhow::Error {
+        let relation = if self.is_newer_than_known() {
+            "过新"
+        } else {
+            "过旧"
+        };
+        anyhow::anyhow!(
+            "cc-switch 数据库版本{}: schema v{}（ccstart 已知最高 v{}），缺少: {}\n提示: 请升级{}",
+            relation,
+            self.version,
+            MAX_SCHEMA_VERSION,
+            missing,
+            if relation == "过新" { " ccstart" } else { " cc-switch 并启动一次以完成迁移" }
+        )
+    }
+
+    /// 可选表是否可用：不存在时为 false，存在但缺少 ccstart 查询的列时报错
+    pub fn table_available(&self, table: &str) -> AppResult<bool> {
+        if let Some(missing) = self.incompatible.get(table) {
+            let missing: Vec<String> = missing.iter().map(|c| format!("{}.{}", table, c)).collect();
+            return Err(self.version_error(&missing.join(", ")));
+        }
+        Ok(self.has_table(table))
+    }
+
+    pub fn has_table(&self, table: &str) -> bool {
+        self.tables.contains(table)
+    }
+
+    pub fn has_provider_column(&self, column: &str) -> bool {
+        self.provider_columns.contains(column)
+    }
+
+    /// providers 表的全部列名
+    pub fn provider_column_names(&self) -> impl Iterator<Item = &str> {
+        self.provider_columns.iter().map(String::as_str)
+    }
+
+    /// 可选列存在时返回列名，否则返回 NULL（用于 SELECT 列表）
+    pub fn provider_column(&self, column: &'static str) -> &'static str {
+        if self.has_provider_column(column) {
+            column
+        } else {
+            "NULL"
+        }
+    }
+
+    /// providers 查询使用的排序子句
+    pub fn provider_order(&self) -> &'static str {
+        if self.has_provider_column("sort_index") {
+            "ORDER BY sort_index, name"
+        } else {
+            "ORDER BY name"
+        }
+    }
+}
+
+/// 读取表的列名
+fn table_columns(conn: &Connection, table: &str) -> AppResult<HashSet<String>> {
+    let mut stmt = conn
+        .prepare(&format!("PRAGMA table_info({})", table))
+        .with_context(|| format!("读取 {} 表结构失败", table))?;
+    stmt.query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<HashSet<_>, _>>()
+        .with_context(|| format!("读取 {} 表结构失败", table))
+}
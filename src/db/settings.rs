@@ -0,0 +1,35 @@
+use crate::error::AppResult;
+use anyhow::Context;
+use rusqlite::{params, OptionalExtension};
+
+use super::{AppType, Database};
+
+/// cc-switch 通用设置数据访问对象（settings 键值表）
+pub struct SettingsDao<'a> {
+    db: &'a Database,
+}
+
+impl<'a> SettingsDao<'a> {
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// 读取设置值；表或键不存在时返回 None
+    pub fn get(&self, key: &str) -> AppResult<Option<String>> {
+        if !self.db.capabilities()?.table_available("settings")? {
+            return Ok(None);
+        }
+        let conn = self.db.connect()?;
+        conn.query_row("SELECT value FROM settings WHERE key = ?1", params![key], |row| {
+            row.get::<_, Option<String>>(0)
+        })
+        .optional()
+        .map(Option::flatten)
+        .with_context(|| format!("读取设置 '{}' 失败", key))
+    }
+
+    /// GUI 合并到每个 provider 的通用配置片段（`common_config_<app>`）
+    pub fn common_config(&self, app: AppType) -> AppResult<Option<String>> {
+        self.get(&format!("common_config_{}", app.as_str()))
+    }
+}
@@ -0,0 +1,79 @@
+use crate::error::AppResult;
+use anyhow::Context;
+
+use super::Database;
+
+/// 已安装技能（对应 skills 表）
+///
+/// `key` 为 `<owner>/<repo>:<directory>` 或仅 `<directory>`
+#[derive(Debug, Clone)]
+pub struct Skill {
+    pub key: String,
+}
+
+/// 技能仓库（对应 skill_repos 表）
+#[derive(Debug, Clone)]
+pub struct SkillRepo {
+    pub owner: String,
+    pub name: String,
+    pub branch: String,
+    pub enabled: bool,
+}
+
+/// 技能数据访问对象
+pub struct SkillDao<'a> {
+    db: &'a Database,
+}
+
+impl<'a> SkillDao<'a> {
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// 获取所有已安装的技能
+    pub fn list_installed(&self) -> AppResult<Vec<Skill>> {
+        if !self.db.capabilities()?.table_available("skills")? {
+            return Ok(Vec::new());
+        }
+        let conn = self.db.connect()?;
+        let mut stmt = conn
+            .prepare("SELECT key FROM skills WHERE installed = 1 ORDER BY key")
+            .with_context(|| "准备查询语句失败")?;
+
+        let iter = stmt
+            .query_map([], |row| Ok(Skill { key: row.get(0)? }))
+            .with_context(|| "执行查询失败")?;
+
+        iter.collect::<Result<Vec<_>, _>>()
+            .with_context(|| "读取查询结果失败")
+    }
+
+    /// 获取所有技能仓库
+    pub fn list_repos(&self) -> AppResult<Vec<SkillRepo>> {
+        if !self.db.capabilities()?.table_available("skill_repos")? {
+            return Ok(Vec::new());
+        }
+        let conn = self.db.connect()?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT owner, name, branch, enabled
+                 FROM skill_repos
+                 ORDER BY owner, name",
+            )
+            .with_context(|| "准备查询语句失败")?;
+
+        let iter = stmt
+            .query_map([], |row| {
+                Ok(SkillRepo {
+                    owner: row.get(0)?,
+                    name: row.get(1)?,
+                    branch: row.get(2)?,
+                    enabled: row.get(3)?,
+                })
+            })
+            .with_context(|| "执行查询失败")?;
+
+        iter.collect::<Result<Vec<_>, _>>()
+            .with_context(|| "读取查询结果失败")
+    }
+}
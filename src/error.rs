@@ -19,6 +19,8 @@ impl fmt::Display for ConfigError {
     }
 }
 
+impl std::error::Error for ConfigError {}
+
 /// I/O 错误占位符（如需分类使用）
 #[allow(dead_code)]
 #[derive(Debug)]
@@ -36,13 +38,27 @@ impl fmt::Display for IoError {
     }
 }
 
-/// JSON 解析错误占位符（如需分类使用）
-#[allow(dead_code)]
-#[derive(Debug)]
+/// JSON 解析错误
+#[derive(Debug, Clone)]
 pub enum JsonError {
     ParseError(String),
 }
 
+impl JsonError {
+    /// 由 serde_json 错误构造，消息中带行列号
+    pub fn from_serde(err: &serde_json::Error) -> Self {
+        let suffix = format!(" at line {} column {}", err.line(), err.column());
+        let message = err.to_string();
+        let message = message.strip_suffix(&suffix).unwrap_or(&message);
+        JsonError::ParseError(format!(
+            "第 {} 行第 {} 列: {}",
+            err.line(),
+            err.column(),
+            message
+        ))
+    }
+}
+
 impl fmt::Display for JsonError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -51,4 +67,6 @@ impl fmt::Display for JsonError {
     }
 }
 
+impl std::error::Error for JsonError {}
+
 pub type AppResult<T> = Result<T, anyhow::Error>;
@@ -2,11 +2,16 @@ mod commands;
 mod config;
 mod db;
 mod error;
+mod source;
 mod utils;
 
-use clap::{CommandFactory, Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
 use clap_complete::{CompleteEnv, Shell};
+use db::{AppType, DbMode};
+use config::paths::{PathOverrides, Paths};
+use source::SourceKind;
 use std::ffi::OsStr;
+use std::path::PathBuf;
 
 #[derive(Debug, Parser)]
 #[command(
@@ -15,11 +20,26 @@ use std::ffi::OsStr;
     about = "ccstart - Claude Settings 配置管理工具 (SQLite 版)",
     long_about = None,
     after_help = "示例:\n  \
+        ccstart                         # 使用 cc-switch 当前配置启动 Claude\n  \
         ccstart list                    # 列出所有配置\n  \
         ccstart packycode               # 使用 packycode 配置启动 Claude\n  \
         ccstart packycode \"help me\"     # 使用配置并传递参数\n  \
         ccstart \"Zhipu GLM\" \"你好\"      # 使用包含空格的配置名称\n  \
         ccstart update                  # 强制刷新所有缓存\n  \
+        ccstart --home work list        # 使用配置文件中的命名 home\n  \
+        ccstart current                 # 查看 cc-switch 当前配置\n  \
+        ccstart use packycode           # 将 packycode 设为 cc-switch 当前配置\n  \
+        ccstart --mcp -exa packycode    # 本次启动不加载 exa MCP 服务器\n  \
+        ccstart mcp list                # 查看会加载的 MCP 服务器\n  \
+        ccstart --prompt review work    # 追加指定提示词作为系统提示\n  \
+        ccstart --skills work           # 加载 cc-switch 中已安装的技能\n  \
+        ccstart endpoints work --probe  # 探测 work 的各端点延迟\n  \
+        ccstart --endpoint auto work    # 使用延迟最低的端点启动\n  \
+        ccstart pin work                # 为当前目录绑定 work，之后 `ccstart` 即使用它\n  \
+        ccstart auto --explain          # 查看 [[rules]] 会选择哪个配置\n  \
+        ccstart --app codex list        # 列出 Codex 配置\n  \
+        ccstart --app codex openai      # 使用 Codex 配置启动 codex\n  \
+        ccstart --app gemini google     # 使用 Gemini 配置启动 gemini\n  \
         ccstart completions bash        # 生成 bash 补全脚本\n\n  \
         提示: 推荐启用动态补全 (实时读取配置列表)\n  \
         Bash: echo \"source <(COMPLETE=bash ccstart)\" >> ~/.bashrc\n  \
@@ -28,14 +48,42 @@ use std::ffi::OsStr;
         PowerShell: $env:COMPLETE = 'powershell'; echo \"ccstart | Out-String | Invoke-Expression\" >> $PROFILE; Remove-Item Env:\\\\COMPLETE"
 )]
 struct Cli {
+    /// 目标应用：决定读取哪类 provider 以及启动哪个 CLI
+    #[arg(long, global = true, value_enum, default_value_t = AppType::Claude)]
+    app: AppType,
+
+    /// provider 数据来源：auto 优先 SQLite（cc-switch.db），不存在时回退到旧版 config.json
+    #[arg(long, global = true, value_enum, default_value_t = SourceKind::Auto)]
+    source: SourceKind,
+
+    /// 数据库打开方式：auto 只读（WAL 且目录不可写时改用 immutable），immutable 不加锁读取
+    #[arg(long, global = true, value_enum, default_value_t = DbMode::Auto, env = "CCSTART_DB_MODE")]
+    db_mode: DbMode,
+
+    /// cc-switch 数据目录（默认 ~/.cc-switch），或配置文件中的命名 home
+    #[arg(long, global = true, value_name = "PATH|NAME", env = "CCSTART_HOME")]
+    home: Option<String>,
+
+    /// cc-switch 数据库路径（默认 <home>/cc-switch.db）
+    #[arg(long, global = true, value_name = "PATH", env = "CCSTART_DB")]
+    db: Option<PathBuf>,
+
+    /// ccstart 缓存目录（默认 $XDG_CACHE_HOME/ccstart）
+    #[arg(long, global = true, value_name = "PATH", env = "CCSTART_CACHE_DIR")]
+    cache_dir: Option<PathBuf>,
+
     /// 当未指定子命令时，作为 `ccstart <name> [args...]` 的 <name>
     #[arg(add = clap_complete::engine::ArgValueCompleter::new(crate::config_name_completer))]
     name: Option<String>,
 
-    /// 透传给底层 `claude` 命令的参数，当使用 `ccstart <name> [args...]` 时生效
+    /// 透传给底层 CLI（claude/codex/gemini）的参数，当使用 `ccstart <name> [args...]` 时生效
     #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
     args: Vec<String>,
 
+    /// 启动选项（作用于 `ccstart <name>`）
+    #[command(flatten)]
+    run_opts: commands::run::RunOptions,
+
     /// 子命令
     #[command(subcommand)]
     command: Option<Commands>,
@@ -43,8 +91,11 @@ struct Cli {
 
 #[derive(Debug, Subcommand)]
 enum Commands {
-    /// 列出所有可用的配置名称
-    List,
+    /// 列出所有可用的配置（`--long` 显示详情，`--format json|tsv` 供脚本使用）
+    List {
+        #[command(flatten)]
+        opts: commands::list::ListOptions,
+    },
 
     /// 更新配置：强制刷新所有缓存文件
     Update,
@@ -60,10 +111,245 @@ enum Commands {
         /// 配置名称
         #[arg(add = clap_complete::engine::ArgValueCompleter::new(crate::config_name_completer))]
         name: String,
-        /// 透传给 `claude` 的参数
+        /// 启动选项
+        #[command(flatten)]
+        opts: commands::run::RunOptions,
+        /// 透传给底层 CLI 的参数
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// 按配置文件中的 [[rules]] 自动选择配置并启动
+    Auto {
+        /// 只显示各规则的判断结果，不启动
+        #[arg(long)]
+        explain: bool,
+        /// 启动选项
+        #[command(flatten)]
+        opts: commands::run::RunOptions,
+        /// 透传给底层 CLI 的参数
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         args: Vec<String>,
     },
+
+    /// 输出 cc-switch 中标记为当前的配置
+    Current,
+
+    /// 列出配置文件中的命名 home 及当前生效的路径
+    Homes,
+
+    /// 新增配置（写入数据库；token 隐藏输入）
+    Add {
+        /// 配置名称
+        name: String,
+        #[command(flatten)]
+        opts: commands::manage::AddOptions,
+    },
+
+    /// 使用 $EDITOR 编辑配置的 settings_config（写入数据库）
+    Edit {
+        /// 配置名称
+        #[arg(add = clap_complete::engine::ArgValueCompleter::new(crate::config_name_completer))]
+        name: String,
+    },
+
+    /// 重命名配置（写入数据库）
+    Rename {
+        /// 配置名称
+        #[arg(add = clap_complete::engine::ArgValueCompleter::new(crate::config_name_completer))]
+        name: String,
+        /// 新名称
+        new_name: String,
+    },
+
+    /// 以新名称复制配置（写入数据库）
+    Clone {
+        /// 配置名称
+        #[arg(add = clap_complete::engine::ArgValueCompleter::new(crate::config_name_completer))]
+        name: String,
+        /// 新名称
+        new_name: String,
+    },
+
+    /// 删除配置及其缓存（写入数据库）
+    Remove {
+        /// 配置名称
+        #[arg(add = clap_complete::engine::ArgValueCompleter::new(crate::config_name_completer))]
+        name: String,
+        /// 不再确认
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// 修改 settings_config 中的字段：`set <name> <path> <value>`，或 `<path>+=<value>` / `<path>-=<value>` 增删数组元素
+    Set {
+        /// [NAME] <PATH> [VALUE]：路径为点分（env.ANTHROPIC_MODEL）或 JSON Pointer（/env/ANTHROPIC_MODEL）
+        #[arg(required = true, num_args = 1..=3, allow_hyphen_values = true, value_name = "ARGS")]
+        args: Vec<String>,
+        #[command(flatten)]
+        target: commands::set::TargetOptions,
+        #[command(flatten)]
+        value: commands::set::ValueOptions,
+    },
+
+    /// 删除 settings_config 中的字段或数组元素：`unset <name> <path>`
+    Unset {
+        /// [NAME] <PATH>
+        #[arg(required = true, num_args = 1..=2, value_name = "ARGS")]
+        args: Vec<String>,
+        #[command(flatten)]
+        target: commands::set::TargetOptions,
+    },
+
+    /// 导出配置（含分类、备注与端点），可加密或清空敏感值
+    Export {
+        #[command(flatten)]
+        opts: commands::transfer::ExportOptions,
+    },
+
+    /// 从导出文件或旧版 config.json 导入配置（写入数据库）
+    Import {
+        #[command(flatten)]
+        opts: commands::transfer::ImportOptions,
+    },
+
+    /// 使用 SQLite 在线备份 API 创建数据库快照
+    Backup {
+        /// 列出已有快照
+        #[arg(long)]
+        list: bool,
+    },
+
+    /// 从快照恢复数据库（先显示 provider 差异）
+    Restore {
+        /// 快照：`latest`、`backup --list` 中的序号、文件名或路径
+        snapshot: String,
+        /// 不再确认
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// 为当前目录绑定配置（写入 .ccstart.toml，`ccstart` / `ccstart .` 时使用）
+    Pin {
+        /// 配置名称
+        #[arg(add = clap_complete::engine::ArgValueCompleter::new(crate::config_name_completer))]
+        name: String,
+        /// 默认的 MCP 调整：`+name` 添加、`-name` 移除（可重复）
+        #[arg(long = "mcp", value_name = "[+|-]NAME", allow_hyphen_values = true)]
+        mcp: Vec<String>,
+        /// 覆盖已有的绑定文件
+        #[arg(long)]
+        force: bool,
+        /// 默认传给 CLI 的参数（写在 `--` 之后）
+        #[arg(last = true)]
+        args: Vec<String>,
+    },
+
+    /// 管理 Claude 订阅账号（OAuth 凭据）
+    Account {
+        #[command(subcommand)]
+        action: AccountAction,
+    },
+
+    /// 将配置设为 cc-switch 的当前配置（写入数据库）
+    Use {
+        /// 配置名称
+        #[arg(add = clap_complete::engine::ArgValueCompleter::new(crate::config_name_completer))]
+        name: String,
+    },
+
+    /// 管理 cc-switch 中的 MCP 服务器
+    Mcp {
+        #[command(subcommand)]
+        action: McpAction,
+    },
+
+    /// 查看 cc-switch 中的提示词
+    Prompts {
+        #[command(subcommand)]
+        action: PromptsAction,
+    },
+
+    /// 显示启动时使用的配置内容（默认遮盖 token/key/secret）
+    Show {
+        /// 配置名称
+        #[arg(add = clap_complete::engine::ArgValueCompleter::new(crate::config_name_completer))]
+        name: String,
+        /// 输出格式
+        #[arg(long, value_enum, default_value_t)]
+        format: commands::show::ShowFormat,
+        /// 显示完整的敏感值
+        #[arg(long)]
+        reveal: bool,
+    },
+
+    /// 列出 provider 的候选端点（provider_endpoints）
+    Endpoints {
+        /// 配置名称
+        #[arg(add = clap_complete::engine::ArgValueCompleter::new(crate::config_name_completer))]
+        name: String,
+        /// 并发探测各端点延迟
+        #[arg(long)]
+        probe: bool,
+        /// 探测结果的缓存时间（秒）
+        #[arg(long, value_name = "SECS", env = "CCSTART_PROBE_TTL", default_value_t = config::endpoint::DEFAULT_PROBE_TTL_SECS)]
+        probe_ttl: u64,
+    },
+
+    /// 列出已安装技能及其本地来源（`--skills` 启动时加载）
+    Skills {
+        /// 先克隆尚未检出的已启用技能仓库（需要网络）
+        #[arg(long)]
+        fetch: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum PromptsAction {
+    /// 列出提示词（✓ 表示默认追加）
+    List,
+    /// 输出提示词内容
+    Show {
+        /// 提示词名称
+        name: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum AccountAction {
+    /// 将当前登录的 Claude 凭据保存为账号
+    Save {
+        /// 账号名称
+        name: String,
+        /// 覆盖同名账号
+        #[arg(long)]
+        force: bool,
+    },
+    /// 切换主配置目录中的 Claude 凭据
+    Use {
+        /// 账号名称
+        name: String,
+        /// 当前凭据未保存时仍然切换（当前凭据将丢失）
+        #[arg(long)]
+        force: bool,
+    },
+    /// 列出已保存的账号（* 表示当前账号）
+    List,
+    /// 删除已保存的账号
+    Remove {
+        /// 账号名称
+        name: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum McpAction {
+    /// 列出 MCP 服务器及启动 Claude 时会加载的集合
+    List {
+        /// 预览调整效果：`+name` 添加、`-name` 移除（可重复）
+        #[arg(long = "mcp", value_name = "[+|-]NAME", allow_hyphen_values = true)]
+        mcp: Vec<String>,
+    },
 }
 
 /// 构建 CLI `Command`（供补全/生成脚本等使用）
@@ -76,29 +362,106 @@ fn run_app() -> error::AppResult<i32> {
 
     let cli = Cli::parse();
 
+    let app = cli.app;
+    let paths = Paths::resolve(&PathOverrides {
+        home: cli.home.clone(),
+        db: cli.db.clone(),
+        cache_dir: cli.cache_dir.clone(),
+    })?;
+    let open_source = || source::open(cli.source, &paths, cli.db_mode);
     let exit_code = match cli.command {
-        Some(Commands::List) => {
-            commands::list::list_configs()?;
+        Some(Commands::List { opts }) => {
+            commands::list::list_configs(&*open_source()?, app, &opts)?;
             0
         }
         Some(Commands::Update) => {
-            commands::update::run()?;
+            commands::update::run(&*open_source()?, &paths, app)?;
             0
         }
         Some(Commands::Completions { shell }) => {
             commands::completions::run(shell)?;
             0
         }
-        Some(Commands::Run { name, args }) => commands::run::run(&name, &args)?,
+        Some(Commands::Run { name, opts, args }) => commands::run::run(&*open_source()?, &paths, app, &name, &args, &opts)?,
+        Some(Commands::Auto { explain, opts, args }) => {
+            commands::auto::auto(&*open_source()?, &paths, app, &args, &opts, explain)?
+        }
+        Some(Commands::Current) => commands::current::show(&*open_source()?, app)?,
+        Some(Commands::Homes) => {
+            commands::homes::list(&paths)?;
+            0
+        }
+        Some(Commands::Add { name, opts }) => {
+            commands::manage::add(&*open_source()?, &paths, app, &name, &opts)?
+        }
+        Some(Commands::Edit { name }) => commands::manage::edit(&*open_source()?, &paths, app, &name)?,
+        Some(Commands::Rename { name, new_name }) => {
+            commands::manage::rename(&*open_source()?, &paths, app, &name, &new_name)?
+        }
+        Some(Commands::Clone { name, new_name }) => {
+            commands::manage::clone(&*open_source()?, &paths, app, &name, &new_name)?
+        }
+        Some(Commands::Remove { name, yes }) => {
+            commands::manage::remove(&*open_source()?, &paths, app, &name, yes)?
+        }
+        Some(Commands::Set {
+            args,
+            target,
+            value,
+        }) => commands::set::set(&*open_source()?, &paths, app, &args, &target, &value)?,
+        Some(Commands::Unset { args, target }) => {
+            commands::set::unset(&*open_source()?, &paths, app, &args, &target)?
+        }
+        Some(Commands::Export { opts }) => commands::transfer::export(&*open_source()?, app, &opts)?,
+        Some(Commands::Import { opts }) => commands::transfer::import(&*open_source()?, &paths, &opts)?,
+        Some(Commands::Backup { list }) => commands::backup::backup(&*open_source()?, &paths, list)?,
+        Some(Commands::Restore { snapshot, yes }) => {
+            commands::backup::restore(&*open_source()?, &paths, &snapshot, yes)?
+        }
+        Some(Commands::Pin { name, mcp, force, args }) => {
+            commands::pin::pin(&*open_source()?, app, &name, &mcp, &args, force)?
+        }
+        Some(Commands::Account { action }) => match action {
+            AccountAction::Save { name, force } => commands::account::save(&paths, &name, force)?,
+            AccountAction::Use { name, force } => commands::account::use_account(&paths, &name, force)?,
+            AccountAction::List => commands::account::list(&paths)?,
+            AccountAction::Remove { name } => commands::account::remove(&paths, &name)?,
+        },
+        Some(Commands::Use { name }) => commands::current::set(&*open_source()?, app, &name)?,
+        Some(Commands::Mcp { action }) => {
+            match action {
+                McpAction::List { mcp } => commands::mcp::list(&*open_source()?, &mcp)?,
+            }
+            0
+        }
+        Some(Commands::Prompts { action }) => match action {
+            PromptsAction::List => {
+                commands::prompts::list(&*open_source()?, app)?;
+                0
+            }
+            PromptsAction::Show { name } => commands::prompts::show(&*open_source()?, app, &name)?,
+        },
+        Some(Commands::Show {
+            name,
+            format,
+            reveal,
+        }) => commands::show::show(&*open_source()?, &paths, app, &name, format, reveal)?,
+        Some(Commands::Endpoints {
+            name,
+            probe,
+            probe_ttl,
+        }) => commands::endpoints::list(&*open_source()?, &paths, app, &name, probe, probe_ttl)?,
+        Some(Commands::Skills { fetch }) => {
+            commands::skills::list(&*open_source()?, &paths, fetch)?;
+            0
+        }
         None => {
             // 无子命令：尝试作为 `ccstart <name> [args...]`
             if let Some(name) = cli.name {
-                commands::run::run(&name, &cli.args)?
+                commands::run::run(&*open_source()?, &paths, app, &name, &cli.args, &cli.run_opts)?
             } else {
-                // 无参数，显示帮助
-                Cli::command().print_help().ok();
-                println!();
-                0
+                // 无名称：使用 cc-switch 中标记为当前的配置
+                commands::run::run_current(&*open_source()?, &paths, app, &cli.args, &cli.run_opts)?
             }
         }
     };
@@ -124,9 +487,23 @@ pub fn config_name_completer(current: &OsStr) -> Vec<clap_complete::engine::Comp
     let needle = current.to_string_lossy().to_string();
     let lower = needle.to_lowercase();
 
-    // 从 SQLite 查询
-    if let Ok(db) = crate::db::Database::open()
-        && let Ok(names) = db.providers().list_names()
+    // 从当前 provider 来源查询
+    let app = completing_value("--app")
+        .and_then(|v| AppType::from_str(&v, true).ok())
+        .unwrap_or_default();
+    let kind = completing_value("--source")
+        .and_then(|v| SourceKind::from_str(&v, true).ok())
+        .unwrap_or_default();
+    let overrides = PathOverrides {
+        home: completing_value("--home").or_else(|| std::env::var("CCSTART_HOME").ok()),
+        db: completing_value("--db")
+            .or_else(|| std::env::var("CCSTART_DB").ok())
+            .map(PathBuf::from),
+        cache_dir: None,
+    };
+    if let Ok(paths) = Paths::resolve(&overrides)
+        && let Ok(source) = source::open(kind, &paths, DbMode::Auto)
+        && let Ok(names) = source.list_names(app)
     {
         for name in names {
             if lower.is_empty() || name.to_lowercase().starts_with(&lower) {
@@ -137,3 +514,18 @@ pub fn config_name_completer(current: &OsStr) -> Vec<clap_complete::engine::Comp
 
     out
 }
+
+/// 补全时从当前命令行中解析选项值（动态补全阶段无法拿到 clap 的解析结果）
+fn completing_value(flag: &str) -> Option<String> {
+    let args: Vec<String> = std::env::args().collect();
+    let prefix = format!("{}=", flag);
+    let mut value = None;
+    for (i, arg) in args.iter().enumerate() {
+        if let Some(v) = arg.strip_prefix(&prefix) {
+            value = Some(v.to_string());
+        } else if arg == flag {
+            value = args.get(i + 1).cloned();
+        }
+    }
+    value
+}
@@ -0,0 +1,156 @@
+use crate::db::{AppType, Provider};
+use crate::error::{AppResult, ConfigError};
+use anyhow::Context;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::ProviderSource;
+
+/// 旧版 config.json 中单个应用的配置段
+#[derive(Debug, Default, Deserialize)]
+struct AppSection {
+    #[serde(default)]
+    providers: BTreeMap<String, LegacyProvider>,
+    #[serde(default)]
+    current: Option<String>,
+}
+
+/// 旧版 config.json 中的 provider
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LegacyProvider {
+    id: Option<String>,
+    name: String,
+    #[serde(default)]
+    settings_config: Value,
+    #[serde(default)]
+    sort_index: Option<i64>,
+    #[serde(default)]
+    category: Option<String>,
+    #[serde(default)]
+    website_url: Option<String>,
+    #[serde(default)]
+    notes: Option<String>,
+    #[serde(default)]
+    icon_color: Option<String>,
+}
+
+/// 旧版 cc-switch 的 `~/.cc-switch/config.json`（version 2）来源
+pub struct LegacyJsonSource {
+    path: PathBuf,
+    apps: BTreeMap<String, AppSection>,
+}
+
+impl LegacyJsonSource {
+    pub fn open(path: &Path) -> AppResult<Self> {
+        let path = path.to_path_buf();
+        if !path.exists() {
+            anyhow::bail!(
+                "配置文件不存在: {}\n提示: 旧版 cc-switch 使用该文件保存配置",
+                path.display()
+            );
+        }
+
+        let content =
+            fs::read_to_string(&path).with_context(|| format!("读取失败: {}", path.display()))?;
+        let root: Value = serde_json::from_str(&content)
+            .with_context(|| format!("解析失败: {}", path.display()))?;
+        Self::from_value(&path, &root)
+    }
+
+    /// 从已解析的 config.json 内容构建（也用于导入 v2 格式的导出文件）
+    pub fn from_value(path: &Path, root: &Value) -> AppResult<Self> {
+        let path = path.to_path_buf();
+        let version = root.get("version").and_then(|v| v.as_i64()).unwrap_or(1);
+        if version != 2 {
+            anyhow::bail!(
+                "不支持的 config.json 版本: v{}（ccstart 仅支持 v2）: {}",
+                version,
+                path.display()
+            );
+        }
+
+        let mut apps = BTreeMap::new();
+        for app in [AppType::Claude, AppType::Codex, AppType::Gemini] {
+            if let Some(section) = root.get(app.as_str()) {
+                let section: AppSection = serde_json::from_value(section.clone())
+                    .with_context(|| format!("解析 {} 配置段失败: {}", app.as_str(), path.display()))?;
+                apps.insert(app.as_str().to_string(), section);
+            }
+        }
+
+        Ok(Self { path, apps })
+    }
+
+    fn to_provider(app: AppType, section: &AppSection, key: &str, p: &LegacyProvider) -> Provider {
+        let duplicate = section.providers.values().filter(|o| o.name == p.name).count() > 1;
+        Provider {
+            id: p.id.clone().unwrap_or_else(|| key.to_string()),
+            app_type: app,
+            name: p.name.clone(),
+            settings_config: p.settings_config.clone(),
+            load_error: None,
+            duplicate,
+            category: p.category.clone(),
+            website_url: p.website_url.clone(),
+            notes: p.notes.clone(),
+            icon_color: p.icon_color.clone(),
+            is_current: section.current.as_deref() == Some(key),
+        }
+    }
+}
+
+impl ProviderSource for LegacyJsonSource {
+    fn describe(&self) -> String {
+        format!("旧版配置文件 {}", self.path.display())
+    }
+
+    fn list_all(&self, app: AppType) -> AppResult<Vec<Provider>> {
+        let Some(section) = self.apps.get(app.as_str()) else {
+            return Ok(Vec::new());
+        };
+        let mut entries: Vec<_> = section.providers.iter().collect();
+        // 与 SQLite 查询保持一致：先按 sortIndex（缺失者在前），再按名称
+        entries.sort_by(|(_, a), (_, b)| (a.sort_index, &a.name).cmp(&(b.sort_index, &b.name)));
+        Ok(entries
+            .into_iter()
+            .map(|(key, p)| Self::to_provider(app, section, key, p))
+            .collect())
+    }
+
+    fn get_by_name(&self, app: AppType, name: &str) -> AppResult<Option<Provider>> {
+        let mut found: Vec<Provider> = self
+            .list_all(app)?
+            .into_iter()
+            .filter(|p| p.name == name)
+            .collect();
+        if found.len() > 1 {
+            let ids: Vec<&str> = found.iter().map(|p| p.id.as_str()).collect();
+            return Err(ConfigError::DuplicateName(format!(
+                "{} 配置 '{}' 存在 {} 个（id: {}）",
+                app.label(),
+                name,
+                found.len(),
+                ids.join(", ")
+            ))
+            .into());
+        }
+        Ok(found.pop())
+    }
+
+    fn get_current(&self, app: AppType) -> AppResult<Option<Provider>> {
+        let Some(section) = self.apps.get(app.as_str()) else {
+            return Ok(None);
+        };
+        let Some(current) = &section.current else {
+            return Ok(None);
+        };
+        Ok(section
+            .providers
+            .get(current)
+            .map(|p| Self::to_provider(app, section, current, p)))
+    }
+}
@@ -0,0 +1,90 @@
+mod legacy;
+pub mod resolve;
+mod sqlite;
+
+pub use legacy::LegacyJsonSource;
+pub use sqlite::SqliteSource;
+
+use crate::config::paths::Paths;
+use crate::db::{AppType, BackupStore, Database, DbMode, Provider, Snapshot};
+use crate::error::AppResult;
+
+/// provider 数据来源的选择方式（`--source`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum SourceKind {
+    /// 优先使用 SQLite 数据库，不存在时回退到旧版 config.json
+    #[default]
+    Auto,
+    /// cc-switch SQLite 数据库（cc-switch.db）
+    Sqlite,
+    /// 旧版 cc-switch 的 config.json（version 2）
+    Json,
+}
+
+/// provider 数据来源：命令只依赖此接口，而非具体的存储格式
+pub trait ProviderSource {
+    /// 用于提示信息的来源描述
+    fn describe(&self) -> String;
+
+    /// 获取指定应用的所有 provider
+    fn list_all(&self, app: AppType) -> AppResult<Vec<Provider>>;
+
+    /// 根据名称获取 provider；存在同名配置时返回 `ConfigError::DuplicateName`
+    fn get_by_name(&self, app: AppType, name: &str) -> AppResult<Option<Provider>>;
+
+    /// 获取标记为当前的 provider
+    fn get_current(&self, app: AppType) -> AppResult<Option<Provider>>;
+
+    /// 获取指定应用的所有 provider 名称（同名配置以 `name#id` 区分）
+    fn list_names(&self, app: AppType) -> AppResult<Vec<String>> {
+        Ok(self
+            .list_all(app)?
+            .iter()
+            .map(|p| p.qualified_name().into_owned())
+            .collect())
+    }
+
+    /// 开启一致性读快照（SQLite）；guard 存活期间的读取看到同一份数据
+    fn snapshot(&self) -> AppResult<Option<Snapshot<'_>>> {
+        Ok(None)
+    }
+
+    /// SQLite 独有的数据（MCP、提示词、技能、端点）；旧版来源返回 None
+    fn database(&self) -> Option<&Database> {
+        None
+    }
+
+    /// 获取 SQLite 数据库，来源不支持时报错
+    fn require_database(&self) -> AppResult<&Database> {
+        self.database().ok_or_else(|| {
+            anyhow::anyhow!(
+                "{} 不支持此功能，需要 cc-switch SQLite 数据库\n提示: 请升级 cc-switch 或使用 --source sqlite",
+                self.describe()
+            )
+        })
+    }
+}
+
+/// 按选择方式打开 provider 来源
+pub fn open(kind: SourceKind, paths: &Paths, mode: DbMode) -> AppResult<Box<dyn ProviderSource>> {
+    let open_sqlite = || -> AppResult<Box<dyn ProviderSource>> {
+        let source = SqliteSource::open(&paths.db, mode)?;
+        Ok(Box::new(if paths.auto_backup {
+            source.with_backups(BackupStore::new(paths.backup_dir.clone(), paths.backup_keep))
+        } else {
+            source
+        }))
+    };
+    match kind {
+        SourceKind::Sqlite => open_sqlite(),
+        SourceKind::Json => Ok(Box::new(LegacyJsonSource::open(&paths.legacy_json)?)),
+        SourceKind::Auto => {
+            if !paths.db.exists() && paths.legacy_json.exists() {
+                Ok(Box::new(LegacyJsonSource::open(&paths.legacy_json)?))
+            } else {
+                // 两者都不存在时，给出 SQLite 的错误提示
+                open_sqlite()
+            }
+        }
+    }
+}
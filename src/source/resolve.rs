@@ -0,0 +1,119 @@
+use crate::db::{AppType, Provider};
+use crate::error::{AppResult, ConfigError};
+use unicode_normalization::UnicodeNormalization;
+
+use super::ProviderSource;
+
+/// "您是否想要" 提示中最多列出的名称数
+const MAX_SUGGESTIONS: usize = 5;
+
+/// 名称解析结果
+pub enum Resolution {
+    /// 唯一命中
+    Found(Provider),
+    /// 多个候选，需要用户进一步明确
+    Ambiguous(Vec<Provider>),
+    /// 无匹配；附带按编辑距离排序的相近名称
+    NotFound(Vec<String>),
+}
+
+/// 按以下顺序解析用户输入的配置名称，命中即停止：
+/// 1. 精确匹配；同名配置可用 `name#id` 指定
+/// 2. 忽略大小写
+/// 3. Unicode NFC 规范化后匹配（同时忽略大小写）
+/// 4. 唯一前缀（忽略大小写、NFC 规范化）
+/// 5. `list` 顺序中的序号（从 1 开始）
+/// 6. `@<id>`
+pub fn resolve(source: &dyn ProviderSource, app: AppType, input: &str) -> AppResult<Resolution> {
+    // 精确匹配走来源的直接查询，避免加载全部配置；重名时交由下方列出候选
+    match source.get_by_name(app, input) {
+        Ok(Some(provider)) => return Ok(Resolution::Found(provider)),
+        Ok(None) => {}
+        Err(e) if matches!(e.downcast_ref(), Some(ConfigError::DuplicateName(_))) => {}
+        Err(e) => return Err(e),
+    }
+    Ok(resolve_in(source.list_all(app)?, input))
+}
+
+fn resolve_in(providers: Vec<Provider>, input: &str) -> Resolution {
+    let lower = input.to_lowercase();
+    let folded = fold(input);
+
+    let matchers: [&dyn Fn(&Provider) -> bool; 5] = [
+        &|p| p.name == input,
+        &|p| input.rsplit_once('#').is_some_and(|(name, id)| p.name == name && p.id == id),
+        &|p| p.name.to_lowercase() == lower,
+        &|p| fold(&p.name) == folded,
+        &|p| !folded.is_empty() && fold(&p.name).starts_with(&folded),
+    ];
+    for matches in matchers {
+        let hits: Vec<&Provider> = providers.iter().filter(|p| matches(p)).collect();
+        match hits.len() {
+            0 => continue,
+            1 => return Resolution::Found(hits[0].clone()),
+            _ => return Resolution::Ambiguous(hits.into_iter().cloned().collect()),
+        }
+    }
+
+    if let Ok(index) = input.parse::<usize>()
+        && index >= 1
+        && index <= providers.len()
+    {
+        return Resolution::Found(providers[index - 1].clone());
+    }
+
+    if let Some(id) = input.strip_prefix('@')
+        && let Some(p) = providers.iter().find(|p| p.id == id)
+    {
+        return Resolution::Found(p.clone());
+    }
+
+    Resolution::NotFound(suggest(&providers, &folded))
+}
+
+/// NFC 规范化并转为小写，用于宽松比较
+fn fold(s: &str) -> String {
+    s.nfc().collect::<String>().to_lowercase()
+}
+
+/// 按编辑距离排序的相近名称；包含输入的名称视为距离 0
+fn suggest(providers: &[Provider], folded: &str) -> Vec<String> {
+    if folded.is_empty() {
+        return Vec::new();
+    }
+    let threshold = (folded.chars().count() / 2).max(2);
+    let mut ranked: Vec<(usize, &str)> = providers
+        .iter()
+        .filter_map(|p| {
+            let name = fold(&p.name);
+            let distance = if name.contains(folded) {
+                0
+            } else {
+                levenshtein(&name, folded)
+            };
+            (distance <= threshold).then_some((distance, p.name.as_str()))
+        })
+        .collect();
+    ranked.sort();
+    ranked
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
+/// 字符级 Levenshtein 编辑距离
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = Vec::with_capacity(b.len() + 1);
+        cur.push(i + 1);
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            cur.push((prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1));
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
@@ -0,0 +1,54 @@
+use crate::db::{AppType, BackupStore, Database, DbMode, Provider, Snapshot};
+use crate::error::AppResult;
+use std::path::Path;
+
+use super::ProviderSource;
+
+/// cc-switch SQLite 数据库来源
+pub struct SqliteSource {
+    db: Database,
+}
+
+impl SqliteSource {
+    pub fn open(path: &Path, mode: DbMode) -> AppResult<Self> {
+        Ok(Self {
+            db: Database::open(path, mode)?,
+        })
+    }
+
+    /// 写入前自动备份到 `store`
+    pub fn with_backups(mut self, store: BackupStore) -> Self {
+        self.db.set_backups(store);
+        self
+    }
+}
+
+impl ProviderSource for SqliteSource {
+    fn describe(&self) -> String {
+        format!("SQLite 数据库 {}", self.db.path().display())
+    }
+
+    fn list_all(&self, app: AppType) -> AppResult<Vec<Provider>> {
+        self.db.providers().list_all(app)
+    }
+
+    fn get_by_name(&self, app: AppType, name: &str) -> AppResult<Option<Provider>> {
+        self.db.providers().get_by_name(app, name)
+    }
+
+    fn get_current(&self, app: AppType) -> AppResult<Option<Provider>> {
+        self.db.providers().get_current(app)
+    }
+
+    fn list_names(&self, app: AppType) -> AppResult<Vec<String>> {
+        self.db.providers().list_names(app)
+    }
+
+    fn database(&self) -> Option<&Database> {
+        Some(&self.db)
+    }
+
+    fn snapshot(&self) -> AppResult<Option<Snapshot<'_>>> {
+        self.db.snapshot().map(Some)
+    }
+}